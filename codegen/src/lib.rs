@@ -0,0 +1,238 @@
+//! Procedural macros for [rocket-basicauth](https://docs.rs/rocket-basicauth),
+//! which are re-exported from there with its `macros` feature and shouldn't be
+//! depended upon directly
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Fields, FnArg, Ident, ItemFn, LitStr, Pat,
+    ReturnType, Token,
+};
+
+/// Arguments given to the `basic_auth` attribute
+struct Args {
+    /// Realm sent inside of challenges, the configured one if missing
+    realm: Option<LitStr>,
+
+    /// Credential verifier taken from managed state
+    verifier: syn::Type,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut realm = None;
+        let mut verifier = None;
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+
+            if key == "realm" && realm.is_none() {
+                realm = Some(input.parse()?);
+            } else if key == "verifier" && verifier.is_none() {
+                verifier = Some(input.parse()?);
+            } else if key == "realm" || key == "verifier" {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("duplicate `{}` argument", key),
+                ));
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "unknown argument, expected `realm` or `verifier`",
+                ));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        let verifier = verifier.ok_or_else(|| {
+            syn::Error::new(
+                Span::call_site(),
+                "missing `verifier = ...` argument naming a `CredentialVerifier`",
+            )
+        })?;
+        Ok(Self { realm, verifier })
+    }
+}
+
+/// Protects a route so that it requires credentials accepted by a verifier,
+/// responding with a challenge otherwise
+///
+/// See `rocket_basicauth::basic_auth` for documentation
+#[proc_macro_attribute]
+pub fn basic_auth(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as Args);
+    let item = parse_macro_input!(input as ItemFn);
+
+    match expand(args, item) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Wraps the route `item` inside of an outer handler which checks credentials
+/// before calling it
+fn expand(args: Args, item: ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = item;
+
+    let mut outer = sig.clone();
+    let mut names = Vec::new();
+    for input in outer.inputs.iter_mut() {
+        let pat = match input {
+            FnArg::Typed(typed) => &mut typed.pat,
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new(
+                    receiver.span(),
+                    "routes protected by `basic_auth` can't take `self`",
+                ))
+            }
+        };
+        match pat.as_mut() {
+            Pat::Ident(ident) if ident.subpat.is_none() => {
+                ident.mutability = None;
+                names.push(ident.ident.clone());
+            }
+            pat => {
+                return Err(syn::Error::new(
+                    pat.span(),
+                    "arguments of routes protected by `basic_auth` must be plain identifiers",
+                ))
+            }
+        }
+    }
+
+    let verifier = args.verifier;
+    outer.inputs.push(parse_quote! {
+        __basic_auth: ::rocket_basicauth::__private::ProtectedGuard<#verifier>
+    });
+    let output = match &sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+    outer.output = parse_quote! {
+        -> ::rocket_basicauth::__private::Protected<#output>
+    };
+
+    let mut inner = sig;
+    inner.ident = format_ident!("__basic_auth_handler");
+    let inner_ident = &inner.ident;
+    let call = match inner.asyncness {
+        Some(_) => quote!(#inner_ident(#(#names),*).await),
+        None => quote!(#inner_ident(#(#names),*)),
+    };
+    let realm = match args.realm {
+        Some(realm) => quote!(::std::option::Option::Some(#realm)),
+        None => quote!(::std::option::Option::None),
+    };
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis #outer {
+            #inner #block
+
+            if let ::std::result::Result::Err(rejected) =
+                ::rocket_basicauth::__private::Protected::check(__basic_auth, #realm)
+            {
+                return rejected;
+            }
+            ::rocket_basicauth::__private::Protected::Allowed(#call)
+        }
+    })
+}
+
+/// Implements `VerifyBasicAuth` for a struct using its fields marked with
+/// `#[basic_auth(username)]` and `#[basic_auth(password_hash)]`
+///
+/// See `rocket_basicauth::BasicAuthVerify` for documentation
+#[proc_macro_derive(BasicAuthVerify, attributes(basic_auth))]
+pub fn derive_basic_auth_verify(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand_derive(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implements `VerifyBasicAuth` for the struct `input`, comparing against its
+/// marked username and password hash fields
+fn expand_derive(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(derive_error(&input)),
+        },
+        _ => return Err(derive_error(&input)),
+    };
+
+    let mut username = None;
+    let mut password_hash = None;
+    for field in fields {
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("basic_auth"))
+        {
+            let kind: Ident = attr.parse_args()?;
+            let slot = if kind == "username" {
+                &mut username
+            } else if kind == "password_hash" {
+                &mut password_hash
+            } else {
+                return Err(syn::Error::new(
+                    kind.span(),
+                    "unknown argument, expected `username` or `password_hash`",
+                ));
+            };
+            if slot.is_some() {
+                return Err(syn::Error::new(
+                    kind.span(),
+                    format!("duplicate `#[basic_auth({})]` field", kind),
+                ));
+            }
+            *slot = field.ident.clone();
+        }
+    }
+
+    let missing = |kind: &str| {
+        syn::Error::new(
+            Span::call_site(),
+            format!("missing a field marked `#[basic_auth({})]`", kind),
+        )
+    };
+    let username = username.ok_or_else(|| missing("username"))?;
+    let password_hash = password_hash.ok_or_else(|| missing("password_hash"))?;
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::rocket_basicauth::VerifyBasicAuth for #ident #ty_generics #where_clause {
+            fn verify(&self, auth: &::rocket_basicauth::BasicAuth) -> bool {
+                auth.verify_hash(
+                    ::std::convert::AsRef::<str>::as_ref(&self.#username),
+                    ::std::convert::AsRef::<str>::as_ref(&self.#password_hash),
+                )
+            }
+        }
+    })
+}
+
+/// Error for deriving `BasicAuthVerify` on anything but a struct with named
+/// fields
+fn derive_error(input: &DeriveInput) -> syn::Error {
+    syn::Error::new(
+        input.ident.span(),
+        "`BasicAuthVerify` can only be derived for structs with named fields",
+    )
+}
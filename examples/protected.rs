@@ -0,0 +1,51 @@
+//! A small app with a page protected by basic authentication, showing the
+//! browser's login prompt for missing or wrong credentials
+//!
+//! Run it with `cargo run --example protected`, then visit
+//! <http://127.0.0.1:8000> and log in as `admin` with the password `hunter2`,
+//! or from the command line:
+//!
+//! ```none
+//! curl -i http://127.0.0.1:8000
+//! curl -i -u admin:hunter2 http://127.0.0.1:8000
+//! ```
+
+use rocket::http::Status;
+use rocket::{catch, catchers, get, launch, routes, Build, Rocket};
+use rocket_basicauth::{BasicAuth, BasicAuthChallenge};
+
+/// Username expected by [index]
+const USERNAME: &str = "admin";
+
+/// Password expected by [index], which should come from somewhere safer than
+/// the source code in a real app
+const PASSWORD: &str = "hunter2";
+
+/// Protected page, only shown once the credentials match
+///
+/// Requests without credentials never reach this route, as the [BasicAuth]
+/// guard forwards them with `401 Unauthorized` instead
+#[get("/")]
+fn index(auth: BasicAuth) -> Result<String, Status> {
+    // constant-time, so response times don't hint at how close a guess was
+    if auth.verify(USERNAME, PASSWORD) {
+        Ok(format!("Welcome back, {}!", auth.username))
+    } else {
+        Err(Status::Unauthorized)
+    }
+}
+
+/// Prompts for credentials whenever a request is unauthorized by adding a
+/// `WWW-Authenticate` header, which is what makes browsers show their prompt
+#[catch(401)]
+fn unauthorized() -> BasicAuthChallenge {
+    BasicAuthChallenge::new("Protected Example").charset()
+}
+
+/// Builds the app, which is public so the integration tests can use it
+#[launch]
+pub fn rocket() -> Rocket<Build> {
+    rocket::build()
+        .mount("/", routes![index])
+        .register("/", catchers![unauthorized])
+}
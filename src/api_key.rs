@@ -0,0 +1,114 @@
+//! Contains the [ApiKeyAuth] request guard for single-token credentials
+
+use crate::{
+    authorization_header, decode_token, invalid_credentials, split_credentials, strip_scheme,
+    BasicAuthConfig, BasicAuthError,
+};
+use rocket::outcome::{try_outcome, Outcome};
+use rocket::request::{self, FromRequest, Request};
+
+/// A request guard for APIs which send a single API key inside of basic
+/// access authentication, containing the `token` found in the user-id slot
+///
+/// Everything before the first [separator](BasicAuthConfig::separator) of the
+/// decoded credentials is used as the token, and unlike
+/// [BasicAuth](crate::BasicAuth), credentials without any separator at all are
+/// accepted too; any password is ignored
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::ApiKeyAuth;
+///
+/// #[get("/charges")]
+/// fn charges(auth: ApiKeyAuth) -> String {
+///     format!("Listing charges for key {}", auth.token)
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().mount("/", routes![charges])
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ApiKeyAuth {
+    /// Raw API key
+    pub token: String,
+}
+
+impl ApiKeyAuth {
+    /// Creates a new [ApiKeyAuth] struct/request guard from a given plaintext
+    /// http auth header or returns a [Option::None] if invalid
+    pub fn new<T: Into<String>>(auth_header: T) -> Option<Self> {
+        Self::with_config(auth_header, &BasicAuthConfig::default())
+    }
+
+    /// Creates a new [ApiKeyAuth] struct/request guard like [ApiKeyAuth::new],
+    /// decoding according to the given `config` instead of the defaults
+    pub fn with_config<T: Into<String>>(auth_header: T, config: &BasicAuthConfig) -> Option<Self> {
+        let key = auth_header.into();
+        let mut decoded = decode_token(strip_scheme(&key, "Basic")?, config).ok()?;
+
+        // a lone token is treated like one with an empty password
+        if !decoded.contains(config.separator) {
+            decoded.push(config.separator);
+        }
+        let (token, _) = split_credentials(decoded.as_bytes(), config).ok()?;
+
+        Some(Self {
+            token: decoded[token].to_string(),
+        })
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ApiKeyAuth {
+    type Error = BasicAuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let key = try_outcome!(authorization_header(request));
+        match ApiKeyAuth::with_config(key, BasicAuthConfig::from_request(request)) {
+            Some(auth) => Outcome::Success(auth),
+            None => invalid_credentials(request),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_check() {
+        // Tests: token:
+        assert_eq!(ApiKeyAuth::new("Basic dG9rZW46").unwrap().token, "token");
+        // Tests: token
+        assert_eq!(ApiKeyAuth::new("Basic dG9rZW4=").unwrap().token, "token");
+        // Tests: token:ignored
+        assert_eq!(
+            ApiKeyAuth::new("Basic dG9rZW46aWdub3JlZA==").unwrap().token,
+            "token"
+        );
+        assert!(ApiKeyAuth::new("Basic !!!").is_none());
+        assert!(ApiKeyAuth::new("Bearer dG9rZW4=").is_none());
+    }
+
+    #[test]
+    fn with_config_check() {
+        let config = BasicAuthConfig {
+            separator: '|',
+            trim_credentials: true,
+            require_non_empty_username: true,
+            ..BasicAuthConfig::default()
+        };
+        let token = |header: &str| ApiKeyAuth::with_config(header, &config).map(|auth| auth.token);
+        // Tests: " token |ignored"
+        assert_eq!(token("Basic IHRva2VuIHxpZ25vcmVk").unwrap(), "token");
+        // Tests: to:ken
+        assert_eq!(token("Basic dG86a2Vu").unwrap(), "to:ken");
+        // Tests: |ignored
+        assert!(token("Basic fGlnbm9yZWQ=").is_none());
+    }
+}
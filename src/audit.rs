@@ -0,0 +1,201 @@
+//! Contains the [AuthAuditor] trait and its [Auditor] managed state, used to
+//! report failed authentication attempts
+
+use crate::{decode_token, strip_scheme, BasicAuthConfig, BasicAuthError};
+use rocket::request::Request;
+use std::fmt;
+use std::net::IpAddr;
+
+/// Receives failed authentication attempts made against the [BasicAuth](crate::BasicAuth)
+/// request guard, e.g. for spotting brute-force attempts
+///
+/// Auditors are retrieved from Rocket's managed state wrapped inside of an
+/// [Auditor], so make sure to [manage](rocket::Rocket::manage) one when
+/// building your app. They're called whenever a request fails with
+/// badly formatted credentials, such as [BasicAuthError::Invalid] or
+/// [BasicAuthError::BadBase64], or [BasicAuthError::BadCount], but never receive
+/// the attempted password
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::{Auditor, AuthAuditor, BasicAuth};
+/// use std::net::IpAddr;
+///
+/// /// Auditor printing failed attempts to stderr
+/// struct StderrAuditor;
+///
+/// impl AuthAuditor for StderrAuditor {
+///     fn on_failure(&self, ip: Option<IpAddr>, username: Option<&str>) {
+///         eprintln!("Failed authentication from {:?} as {:?}", ip, username);
+///     }
+/// }
+///
+/// #[get("/")]
+/// fn index(auth: BasicAuth) -> String {
+///     format!("Hello, {}!", auth.username)
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build()
+///         .manage(Auditor::new(StderrAuditor))
+///         .mount("/", routes![index])
+/// }
+/// ```
+pub trait AuthAuditor: Send + Sync + 'static {
+    /// Called when authentication fails for the client with the given `ip`,
+    /// with the attempted `username` if it could be decoded from credentials
+    /// using the `Basic` scheme, so tokens of other schemes are never leaked
+    fn on_failure(&self, ip: Option<IpAddr>, username: Option<&str>);
+}
+
+/// Managed state holding the [AuthAuditor] which failed authentication
+/// attempts are reported to, see [AuthAuditor] for an example
+pub struct Auditor(Box<dyn AuthAuditor>);
+
+impl Auditor {
+    /// Creates new managed state reporting to the given `auditor`
+    pub fn new<A: AuthAuditor>(auditor: A) -> Self {
+        Self(Box::new(auditor))
+    }
+
+    /// Reports a failed attempt for the `request` to the auditor if one is
+    /// managed, decoding the attempted username from `auth_header` if given
+    pub(crate) fn report(request: &Request<'_>, error: &BasicAuthError, auth_header: Option<&str>) {
+        if !matches!(
+            error,
+            BasicAuthError::Invalid
+                | BasicAuthError::BadBase64
+                | BasicAuthError::BadUtf8
+                | BasicAuthError::NoSeparator
+                | BasicAuthError::BadCount
+        ) {
+            return;
+        }
+        if let Some(auditor) = request.rocket().state::<Self>() {
+            let config = BasicAuthConfig::from_request(request);
+            // only basic credentials are decoded, as the tokens of other
+            // schemes such as bearer ones are secrets
+            let decoded = auth_header
+                .and_then(|header| strip_scheme(header, "Basic"))
+                .and_then(|token| decode_token(token, config).ok());
+            let username = decoded
+                .as_deref()
+                .and_then(|creds| creds.split_once(config.separator))
+                .map(|(username, _)| username);
+
+            auditor.0.on_failure(request.client_ip(), username);
+        }
+    }
+}
+
+impl fmt::Debug for Auditor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Auditor").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BasicAuth;
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+    use std::sync::{Arc, Mutex};
+
+    type Failures = Arc<Mutex<Vec<(Option<IpAddr>, Option<String>)>>>;
+
+    struct RecordingAuditor(Failures);
+
+    impl AuthAuditor for RecordingAuditor {
+        fn on_failure(&self, ip: Option<IpAddr>, username: Option<&str>) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((ip, username.map(str::to_string)));
+        }
+    }
+
+    #[get("/")]
+    fn index(auth: BasicAuth) -> String {
+        auth.username.clone()
+    }
+
+    #[test]
+    fn auditor_check() {
+        let failures = Failures::default();
+        let rocket = rocket::build()
+            .manage(Auditor::new(RecordingAuditor(failures.clone())))
+            .mount("/", routes![index]);
+        let client = Client::tracked(rocket).unwrap();
+        let ip: IpAddr = "192.0.2.1".parse().unwrap();
+        let status = |headers: &[&'static str]| {
+            let mut req = client.get("/").remote((ip, 8000).into());
+            for header in headers {
+                req = req.header(Header::new("Authorization", *header));
+            }
+            req.dispatch().status()
+        };
+
+        // Tests: name:password
+        assert_eq!(status(&["Basic bmFtZTpwYXNzd29yZA=="]), Status::Ok);
+        assert_eq!(status(&[]), Status::Unauthorized);
+        assert!(failures.lock().unwrap().is_empty());
+
+        // Tests: mallory
+        assert_eq!(status(&["Basic bWFsbG9yeQ=="]), Status::BadRequest);
+        assert_eq!(status(&["Basic !!!"]), Status::BadRequest);
+        // Tests: name:password
+        assert_eq!(
+            status(&["Basic bmFtZTpwYXNzd29yZA==", "Basic bmFtZTpwYXNzd29yZA=="]),
+            Status::BadRequest
+        );
+        assert_eq!(*failures.lock().unwrap(), vec![(Some(ip), None); 3]);
+        failures.lock().unwrap().clear();
+
+        // Tests: mallory:pass, never decoded without the basic scheme
+        assert_eq!(status(&["Bearer bWFsbG9yeTpwYXNz"]), Status::BadRequest);
+        assert_eq!(status(&["bWFsbG9yeTpwYXNz"]), Status::BadRequest);
+        assert_eq!(*failures.lock().unwrap(), vec![(Some(ip), None); 2]);
+    }
+
+    #[test]
+    fn auditor_username() {
+        let failures = Failures::default();
+        let figment =
+            rocket::Config::figment().merge(("basic_auth.require_non_empty_username", true));
+        let rocket = rocket::custom(figment)
+            .manage(Auditor::new(RecordingAuditor(failures.clone())))
+            .mount("/", routes![index]);
+        let client = Client::tracked(rocket).unwrap();
+        let status = |header: &'static str| {
+            client
+                .get("/")
+                .header(Header::new("Authorization", header))
+                .dispatch()
+                .status()
+        };
+
+        // Tests: :pass
+        assert_eq!(status("Basic OnBhc3M="), Status::BadRequest);
+        assert_eq!(status("Bearer OnBhc3M="), Status::BadRequest);
+        assert_eq!(
+            *failures.lock().unwrap(),
+            vec![(None, Some(String::new())), (None, None)]
+        );
+    }
+
+    #[test]
+    fn auditor_unmanaged() {
+        let client = Client::tracked(rocket::build().mount("/", routes![index])).unwrap();
+        let resp = client
+            .get("/")
+            .header(Header::new("Authorization", "Basic !!!"))
+            .dispatch();
+        assert_eq!(resp.status(), Status::BadRequest);
+    }
+}
@@ -0,0 +1,299 @@
+//! Contains the [CachedVerifier] which remembers the results of another
+//! [CredentialVerifier] for a short while
+
+use crate::{Clock, CredentialVerifier, SystemClock};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Cached result of verifying a single set of credentials
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    verified: bool,
+    since: Instant,
+    used: Instant,
+}
+
+/// A [CredentialVerifier] wrapping another one, remembering its results for a
+/// short time-to-live so repeated requests with the same credentials skip
+/// expensive password hashing such as bcrypt or argon2
+///
+/// Up to `capacity` results are kept, evicting the least recently used one
+/// when full, and each is forgotten once its time-to-live elapses. Results are
+/// keyed on a 128-bit keyed hash of the credentials rather than the
+/// credentials themselves, so passwords aren't kept around in memory. Note
+/// that changes to the wrapped verifier's users, such as a removed user, only
+/// take effect once the results cached for them expire
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::{BasicAuthStore, CachedVerifier, VerifiedBasicAuth};
+/// use std::time::Duration;
+///
+/// #[get("/")]
+/// fn index(auth: VerifiedBasicAuth<CachedVerifier<BasicAuthStore>>) -> String {
+///     format!("Welcome back, {}!", auth.username)
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let mut store = BasicAuthStore::new();
+///     store.insert("alice", "wonderland");
+///
+///     rocket::build()
+///         .manage(CachedVerifier::new(store).ttl(Duration::from_secs(10)))
+///         .mount("/", routes![index])
+/// }
+/// ```
+pub struct CachedVerifier<V: CredentialVerifier> {
+    verifier: V,
+    capacity: usize,
+    ttl: Duration,
+    clock: Box<dyn Clock>,
+    hashers: (RandomState, RandomState),
+    entries: Mutex<HashMap<(u64, u64), Entry>>,
+}
+
+impl<V: CredentialVerifier> CachedVerifier<V> {
+    /// Default number of results kept at once
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    /// Default time-to-live of each result
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(30);
+
+    /// Creates a new cache in front of the given `verifier`, keeping up to
+    /// [CachedVerifier::DEFAULT_CAPACITY] results for
+    /// [CachedVerifier::DEFAULT_TTL] each
+    pub fn new(verifier: V) -> Self {
+        Self {
+            verifier,
+            capacity: Self::DEFAULT_CAPACITY,
+            ttl: Self::DEFAULT_TTL,
+            clock: Box::new(SystemClock),
+            hashers: (RandomState::new(), RandomState::new()),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the maximum number of results kept at once, where `0` disables
+    /// caching entirely
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets how long each result is kept for
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Replaces the [Clock] used to expire results, which is [SystemClock] by
+    /// default
+    pub fn with_clock<C: Clock>(mut self, clock: C) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Gets the wrapped verifier
+    pub fn inner(&self) -> &V {
+        &self.verifier
+    }
+
+    /// Forgets all cached results, e.g. after changing the wrapped verifier's
+    /// users
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Hashes credentials into the key their result is cached under
+    fn key(&self, username: &str, password: &str) -> (u64, u64) {
+        let creds = (username, password);
+        (
+            self.hashers.0.hash_one(creds),
+            self.hashers.1.hash_one(creds),
+        )
+    }
+
+    /// Checks if the result cached at `since` has expired at `now`
+    fn expired(&self, since: Instant, now: Instant) -> bool {
+        now.saturating_duration_since(since) >= self.ttl
+    }
+
+    /// Gets the unexpired result cached under `key`, if any
+    fn get(&self, key: (u64, u64)) -> Option<bool> {
+        let now = self.clock.now();
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get_mut(&key)?;
+
+        if self.expired(entry.since, now) {
+            entries.remove(&key);
+            return None;
+        }
+        entry.used = now;
+        Some(entry.verified)
+    }
+
+    /// Caches the `verified` result under `key`, evicting the least recently
+    /// used result if full
+    fn insert(&self, key: (u64, u64), verified: bool) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let now = self.clock.now();
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| !self.expired(entry.since, now));
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            let lru = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(key, _)| *key);
+            if let Some(lru) = lru {
+                entries.remove(&lru);
+            }
+        }
+        entries.insert(
+            key,
+            Entry {
+                verified,
+                since: now,
+                used: now,
+            },
+        );
+    }
+}
+
+impl<V: CredentialVerifier + fmt::Debug> fmt::Debug for CachedVerifier<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedVerifier")
+            .field("verifier", &self.verifier)
+            .field("capacity", &self.capacity)
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+#[rocket::async_trait]
+impl<V: CredentialVerifier> CredentialVerifier for CachedVerifier<V> {
+    async fn verify(&self, username: &str, password: &str) -> bool {
+        let key = self.key(username, password);
+        if let Some(verified) = self.get(key) {
+            return verified;
+        }
+
+        let verified = self.verifier.verify(username, password).await;
+        self.insert(key, verified);
+        verified
+    }
+
+    async fn permits(&self, username: &str) -> bool {
+        self.verifier.permits(username).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VerifiedBasicAuth;
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Verifier counting how many times it's been called
+    struct CountingVerifier(Arc<AtomicUsize>);
+
+    #[rocket::async_trait]
+    impl CredentialVerifier for CountingVerifier {
+        async fn verify(&self, username: &str, password: &str) -> bool {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            username == "name" && password == "password"
+        }
+    }
+
+    #[derive(Clone)]
+    struct TestClock(Arc<Mutex<Instant>>);
+
+    impl Clock for TestClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    #[get("/")]
+    fn index(auth: VerifiedBasicAuth<CachedVerifier<CountingVerifier>>) -> String {
+        auth.username.clone()
+    }
+
+    #[test]
+    fn cached_guard() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let rocket = rocket::build()
+            .manage(CachedVerifier::new(CountingVerifier(calls.clone())))
+            .mount("/", routes![index]);
+        let client = Client::tracked(rocket).unwrap();
+        let get = |header: &'static str| {
+            client
+                .get("/")
+                .header(Header::new("Authorization", header))
+                .dispatch()
+                .status()
+        };
+
+        // Tests: name:password
+        assert_eq!(get("Basic bmFtZTpwYXNzd29yZA=="), Status::Ok);
+        assert_eq!(get("Basic bmFtZTpwYXNzd29yZA=="), Status::Ok);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        // Tests: name:wrong
+        assert_eq!(get("Basic bmFtZTp3cm9uZw=="), Status::Unauthorized);
+        assert_eq!(get("Basic bmFtZTp3cm9uZw=="), Status::Unauthorized);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[rocket::async_test]
+    async fn cache_expiry_and_eviction() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let clock = TestClock(Arc::new(Mutex::new(Instant::now())));
+        let cache = CachedVerifier::new(CountingVerifier(calls.clone()))
+            .capacity(2)
+            .ttl(Duration::from_secs(10))
+            .with_clock(clock.clone());
+        let advance = |secs| *clock.0.lock().unwrap() += Duration::from_secs(secs);
+
+        assert!(cache.verify("name", "password").await);
+        advance(9);
+        assert!(cache.verify("name", "password").await);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        advance(1);
+        assert!(cache.verify("name", "password").await);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // least recently used result is evicted once full
+        assert!(!cache.verify("a", "a").await);
+        advance(1);
+        assert!(cache.verify("name", "password").await);
+        assert!(!cache.verify("b", "b").await);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        assert!(cache.verify("name", "password").await);
+        assert!(!cache.verify("a", "a").await);
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+
+        cache.clear();
+        assert!(cache.verify("name", "password").await);
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+        assert!(
+            !CachedVerifier::new(CountingVerifier(calls.clone()))
+                .capacity(0)
+                .verify("a", "a")
+                .await
+        );
+    }
+}
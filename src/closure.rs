@@ -0,0 +1,104 @@
+//! Contains the [VerifierFn] managed state and the [ClosureBasicAuth] request
+//! guard which checks credentials using a closure
+
+use crate::{CredentialVerifier, VerifiedBasicAuth};
+use std::fmt;
+
+/// Managed state holding a closure which checks credentials, for when
+/// implementing [CredentialVerifier] is overkill
+///
+/// As Rocket's managed state is shared between every request handled across
+/// its worker threads, the closure has to be `Send + Sync + 'static`, meaning
+/// it can't borrow anything and has to [move](https://doc.rust-lang.org/std/keyword.move.html)
+/// or share any data it captures, e.g. behind an [Arc](std::sync::Arc)
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::{ClosureBasicAuth, VerifierFn};
+///
+/// #[get("/")]
+/// fn index(auth: ClosureBasicAuth) -> String {
+///     format!("Welcome back, {}!", auth.username)
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let password = std::env::var("ADMIN_PASSWORD").unwrap();
+///
+///     rocket::build()
+///         .manage(VerifierFn::new(move |username, given| {
+///             username == "admin" && given == password
+///         }))
+///         .mount("/", routes![index])
+/// }
+/// ```
+#[allow(clippy::type_complexity)]
+pub struct VerifierFn(Box<dyn Fn(&str, &str) -> bool + Send + Sync>);
+
+impl VerifierFn {
+    /// Creates new managed state checking credentials using the given
+    /// `verifier`, which is called with the username and password and returns
+    /// if they're valid
+    pub fn new<F: Fn(&str, &str) -> bool + Send + Sync + 'static>(verifier: F) -> Self {
+        Self(Box::new(verifier))
+    }
+}
+
+impl fmt::Debug for VerifierFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VerifierFn").finish_non_exhaustive()
+    }
+}
+
+#[rocket::async_trait]
+impl CredentialVerifier for VerifierFn {
+    async fn verify(&self, username: &str, password: &str) -> bool {
+        (self.0)(username, password)
+    }
+}
+
+/// A request guard containing credentials which have been accepted by the
+/// [VerifierFn] closure inside of Rocket's managed state, see
+/// [VerifiedBasicAuth] for its behaviour
+pub type ClosureBasicAuth = VerifiedBasicAuth<VerifierFn>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+
+    #[get("/")]
+    fn index(auth: ClosureBasicAuth) -> String {
+        auth.username.clone()
+    }
+
+    #[test]
+    fn closure_guard() {
+        let rocket = rocket::build()
+            .manage(VerifierFn::new(|username, password| {
+                username == "name" && password == "password"
+            }))
+            .mount("/", routes![index]);
+        let client = Client::tracked(rocket).unwrap();
+        let get = |header: &'static str| {
+            let resp = client
+                .get("/")
+                .header(Header::new("Authorization", header))
+                .dispatch();
+            (resp.status(), resp.into_string())
+        };
+
+        // Tests: name:password
+        assert_eq!(
+            get("Basic bmFtZTpwYXNzd29yZA=="),
+            (Status::Ok, Some("name".to_string()))
+        );
+        // Tests: name:wrong
+        assert_eq!(get("Basic bmFtZTp3cm9uZw==").0, Status::Unauthorized);
+    }
+}
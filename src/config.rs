@@ -0,0 +1,532 @@
+//! Contains the [BasicAuthConfig] settings, read from Rocket's configuration
+
+use crate::BasicAuthError;
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+use rocket::fairing::AdHoc;
+use rocket::figment::providers::Serialized;
+use rocket::figment::value::{Dict, Map};
+use rocket::figment::{Metadata, Profile, Provider};
+use rocket::http::Status;
+use rocket::request::Request;
+use rocket::serde::{Deserialize, Serialize};
+
+/// Configuration for this crate, read from the `basic_auth` key of Rocket's
+/// [figment](rocket::figment::Figment), e.g. inside of your `Rocket.toml`:
+///
+/// ```toml
+/// [default.basic_auth]
+/// realm = "My Website"
+/// max_header_len = 1024
+/// max_decoded_bytes = 4096
+/// multi_header_policy = "first"
+/// missing_policy = "fail"
+/// base64_alphabet = "url_safe"
+/// base64_padding = "strict"
+/// allow_insecure = true
+/// trim_credentials = true
+/// require_non_empty_username = true
+/// strip_token_whitespace = true
+/// ignore_token_params = true
+/// uniform_rejection = true
+/// strict_token68 = true
+/// separator = "|"
+/// bad_count_header = "X-Authorization-Count"
+///
+/// [default.basic_auth.status_policy]
+/// invalid = 401
+/// bad_count = 422
+/// ```
+///
+/// The full set of keys and their defaults is:
+///
+/// | Key                          | Default        |
+/// |------------------------------|----------------|
+/// | `realm`                      | `"Restricted"` |
+/// | `max_header_len`             | no limit       |
+/// | `max_decoded_bytes`          | `8192`         |
+/// | `multi_header_policy`        | `"reject"`     |
+/// | `missing_policy`             | `"forward"`    |
+/// | `status_policy.invalid`      | `400`          |
+/// | `status_policy.bad_count`    | `400`          |
+/// | `status_policy.missing`      | `401`          |
+/// | `base64_alphabet`            | `"standard"`   |
+/// | `base64_padding`             | `"lenient"`    |
+/// | `allow_insecure`             | `false`        |
+/// | `trim_credentials`           | `false`        |
+/// | `require_non_empty_username` | `false`        |
+/// | `strip_token_whitespace`     | `false`        |
+/// | `ignore_token_params`        | `false`        |
+/// | `uniform_rejection`          | `false`        |
+/// | `strict_token68`             | `false`        |
+/// | `separator`                  | `':'`          |
+/// | `bad_count_header`           | no header      |
+///
+/// Any missing keys fall back to their [Default] values, and the figment is
+/// read again for every request using it, only being cached for the rest of
+/// that request. Attaching [BasicAuthConfig::fairing] instead reads it once as
+/// Rocket ignites, failing to ignite if it's invalid rather than silently
+/// falling back to the defaults, so it's recommended. Alternatively, a
+/// [BasicAuthConfig] can be [managed](rocket::Rocket::manage) directly, which
+/// takes priority over the figment
+///
+/// This is also a figment [Provider] of its values under the `basic_auth` key,
+/// so it can be merged into Rocket's figment to change the defaults in code:
+///
+/// ```rust
+/// use rocket_basicauth::BasicAuthConfig;
+///
+/// let config = BasicAuthConfig {
+///     realm: "My Website".to_string(),
+///     ..BasicAuthConfig::default()
+/// };
+/// let figment = rocket::Config::figment().join(config);
+/// let rocket = rocket::custom(figment).attach(BasicAuthConfig::fairing());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", default)]
+pub struct BasicAuthConfig {
+    /// Realm sent inside of `WWW-Authenticate` headers, which browsers show
+    /// inside of their login prompt, defaulting to `"Restricted"`
+    pub realm: String,
+
+    /// Maximum length in bytes of `Authorization` headers, past which requests
+    /// fail with `413 Payload Too Large` before any decoding happens, defaulting
+    /// to no limit
+    pub max_header_len: Option<usize>,
+
+    /// Maximum number of bytes credentials may decode to, estimated as 3/4 of
+    /// the length of the token following the scheme, past which requests fail
+    /// with `413 Payload Too Large` before anything gets decoded, defaulting to
+    /// 8 KiB
+    pub max_decoded_bytes: usize,
+
+    /// What to do when a request has multiple `Authorization` headers,
+    /// defaulting to [MultiHeaderPolicy::Reject]
+    pub multi_header_policy: MultiHeaderPolicy,
+
+    /// What to do when a request has no `Authorization` header, defaulting to
+    /// [MissingPolicy::Forward]
+    pub missing_policy: MissingPolicy,
+
+    /// Statuses the [BasicAuth](crate::BasicAuth) request guard responds with
+    /// for each failure reason, defaulting to those of [BasicAuthError](crate::BasicAuthError)
+    pub status_policy: StatusPolicy,
+
+    /// Base64 alphabet credentials are decoded with, defaulting to
+    /// [Base64Alphabet::Standard]
+    pub base64_alphabet: Base64Alphabet,
+
+    /// How strictly padding of base64-encoded credentials is checked,
+    /// defaulting to [Base64Padding::Lenient]
+    pub base64_padding: Base64Padding,
+
+    /// Whether [SecureBasicAuth](crate::SecureBasicAuth) accepts credentials
+    /// sent over plaintext http, which is useful for local development,
+    /// defaulting to `false`
+    pub allow_insecure: bool,
+
+    /// Whether ASCII whitespace surrounding decoded usernames and passwords is
+    /// trimmed, for clients which accidentally send trailing spaces or `\r\n`,
+    /// defaulting to `false` as passwords may intentionally contain spaces
+    pub trim_credentials: bool,
+
+    /// Whether credentials with an empty username such as `:password` are
+    /// rejected with [BasicAuthError::Invalid](crate::BasicAuthError::Invalid),
+    /// defaulting to `false`
+    pub require_non_empty_username: bool,
+
+    /// Whether ASCII whitespace inside of base64-encoded credentials is removed
+    /// before decoding, for clients which line-wrap long tokens MIME-style,
+    /// defaulting to `false` so such tokens are rejected
+    pub strip_token_whitespace: bool,
+
+    /// Whether anything following the base64-encoded credentials after a
+    /// comma or whitespace is ignored, for nonstandard intermediaries which
+    /// append parameters like `Basic dXNlcjpwYXNz, some-param`, defaulting to
+    /// `false` so such headers are rejected. This takes priority over
+    /// [BasicAuthConfig::strip_token_whitespace]
+    pub ignore_token_params: bool,
+
+    /// Whether malformed credentials are rejected only after doing dummy work
+    /// similar to decoding well-formed ones, so attackers can't as easily tell
+    /// them apart from credentials rejected by a verifier through timing,
+    /// defaulting to `false`. This is best-effort only, as the dummy work
+    /// can't exactly match the real decoding or what verifiers do afterwards
+    pub uniform_rejection: bool,
+
+    /// Whether base64-encoded credentials have to match the `token68` grammar
+    /// of [RFC 7235](https://datatracker.ietf.org/doc/html/rfc7235#section-2.1)
+    /// exactly, being one or more of `A-Z`, `a-z`, `0-9`, `-`, `.`, `_`, `~`,
+    /// `+` or `/` followed by any `=` padding, before being decoded. Any other
+    /// characters fail with [BasicAuthError::Invalid], which includes the
+    /// whitespace and parameters that [BasicAuthConfig::strip_token_whitespace]
+    /// and [BasicAuthConfig::ignore_token_params] would otherwise allow,
+    /// defaulting to `false`
+    pub strict_token68: bool,
+
+    /// Character splitting decoded credentials into their username and
+    /// password, defaulting to `:`
+    ///
+    /// This deviates from [RFC 7617](https://datatracker.ietf.org/doc/html/rfc7617#section-2),
+    /// which always uses a colon, and only exists for legacy clients which
+    /// send another character instead. Changing it means standard clients
+    /// whose passwords contain the new separator are split in the wrong place,
+    /// and that credentials from standard clients containing no such character
+    /// fail with [BasicAuthError::NoSeparator](crate::BasicAuthError::NoSeparator)
+    pub separator: char,
+
+    /// Name of a diagnostic header sent back on responses to requests which
+    /// failed with [BasicAuthError::BadCount](crate::BasicAuthError::BadCount),
+    /// holding how many `Authorization` headers were received, e.g. to tell a
+    /// proxy duplicating headers apart from a malformed one, defaulting to not
+    /// sending any. Its status can be changed using [StatusPolicy::bad_count],
+    /// e.g. to `421 Misdirected Request`
+    ///
+    /// This header is only sent once [BasicAuthConfig::fairing] is attached,
+    /// as it's added by a response fairing which that one attaches
+    pub bad_count_header: Option<String>,
+}
+
+/// Number of credential headers of a request which failed with
+/// [BasicAuthError::BadCount](crate::BasicAuthError::BadCount), kept inside of
+/// its local cache for [BasicAuthConfig::bad_count_header]
+pub(crate) struct BadCountHeaders(pub(crate) usize);
+
+impl BasicAuthConfig {
+    /// Key inside of Rocket's figment which this configuration is read from
+    pub const KEY: &'static str = "basic_auth";
+
+    /// Retrieves the configuration in use for a request, preferring managed
+    /// state over Rocket's figment and then falling back to the defaults
+    ///
+    /// Without managed state, such as from [BasicAuthConfig::fairing], the
+    /// figment is deserialized once per request and kept in its local cache
+    pub fn from_request<'r>(request: &'r Request<'_>) -> &'r Self {
+        match request.rocket().state::<Self>() {
+            Some(config) => config,
+            None => request.local_cache(|| {
+                request
+                    .rocket()
+                    .figment()
+                    .extract_inner(Self::KEY)
+                    .unwrap_or_default()
+            }),
+        }
+    }
+
+    /// Decodes base64-encoded credentials, first cutting off any trailing
+    /// parameters if [BasicAuthConfig::ignore_token_params] is set and then
+    /// removing any whitespace inside of them if
+    /// [BasicAuthConfig::strip_token_whitespace] is set
+    pub(crate) fn decode_base64<T: AsRef<[u8]>>(&self, token: T) -> Option<Vec<u8>> {
+        let mut token = token.as_ref();
+        if self.ignore_token_params {
+            let end = token
+                .iter()
+                .position(|c| *c == b',' || c.is_ascii_whitespace())
+                .unwrap_or(token.len());
+            token = &token[..end];
+        }
+        if self.strip_token_whitespace {
+            let stripped: Vec<u8> = token
+                .iter()
+                .copied()
+                .filter(|c| !c.is_ascii_whitespace())
+                .collect();
+            self.base64_engine().decode(stripped).ok()
+        } else {
+            self.base64_engine().decode(token).ok()
+        }
+    }
+
+    /// Checks if a base64-encoded `token` may be decoded, which is always the
+    /// case unless [BasicAuthConfig::strict_token68] is set and it doesn't
+    /// match the `token68` grammar
+    pub(crate) fn allows_token<T: AsRef<[u8]>>(&self, token: T) -> bool {
+        if !self.strict_token68 {
+            return true;
+        }
+
+        let token = token.as_ref();
+        let padding = token.iter().rev().take_while(|c| **c == b'=').count();
+        let chars = &token[..token.len() - padding];
+        !chars.is_empty()
+            && chars
+                .iter()
+                .all(|c| c.is_ascii_alphanumeric() || b"-._~+/".contains(c))
+    }
+
+    /// Creates a fairing which reads the configuration from Rocket's figment as
+    /// it ignites and [manages](rocket::Rocket::manage) it, failing to ignite
+    /// if the configuration is invalid
+    ///
+    /// This also attaches a response fairing sending the
+    /// [BasicAuthConfig::bad_count_header] when it's set
+    pub fn fairing() -> AdHoc {
+        AdHoc::try_on_ignite("Basic Authentication Config", |rocket| async {
+            match rocket.figment().extract_inner::<Self>(Self::KEY) {
+                Ok(config) => Ok(rocket.manage(config).attach(AdHoc::on_response(
+                    "Basic Authentication Diagnostics",
+                    |request, response| {
+                        Box::pin(async move {
+                            let name = match &Self::from_request(request).bad_count_header {
+                                Some(name) => name,
+                                None => return,
+                            };
+                            let count = request.local_cache(|| BadCountHeaders(0)).0;
+                            if count > 0 {
+                                response.set_raw_header(name.clone(), count.to_string());
+                            }
+                        })
+                    },
+                ))),
+                Err(_err) => {
+                    #[cfg(feature = "log")]
+                    log::error!("Invalid basic authentication configuration: {}", _err);
+                    Err(rocket)
+                }
+            }
+        })
+    }
+
+    /// Creates the base64 engine credentials are decoded with
+    fn base64_engine(&self) -> GeneralPurpose {
+        let alphabet = match self.base64_alphabet {
+            Base64Alphabet::Standard => &alphabet::STANDARD,
+            Base64Alphabet::UrlSafe => &alphabet::URL_SAFE,
+        };
+        let padding = match self.base64_padding {
+            Base64Padding::Lenient => DecodePaddingMode::Indifferent,
+            Base64Padding::Strict => DecodePaddingMode::RequireCanonical,
+        };
+
+        GeneralPurpose::new(
+            alphabet,
+            GeneralPurposeConfig::new().with_decode_padding_mode(padding),
+        )
+    }
+}
+
+impl Default for BasicAuthConfig {
+    fn default() -> Self {
+        Self {
+            realm: "Restricted".to_string(),
+            max_header_len: None,
+            max_decoded_bytes: 8 * 1024,
+            multi_header_policy: MultiHeaderPolicy::default(),
+            missing_policy: MissingPolicy::default(),
+            status_policy: StatusPolicy::default(),
+            base64_alphabet: Base64Alphabet::default(),
+            base64_padding: Base64Padding::default(),
+            allow_insecure: false,
+            trim_credentials: false,
+            require_non_empty_username: false,
+            strip_token_whitespace: false,
+            ignore_token_params: false,
+            uniform_rejection: false,
+            strict_token68: false,
+            separator: ':',
+            bad_count_header: None,
+        }
+    }
+}
+
+impl Provider for BasicAuthConfig {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("Basic Authentication Config")
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, rocket::figment::Error> {
+        Serialized::default(Self::KEY, self).data()
+    }
+}
+
+/// Policy deciding what happens when a request has multiple `Authorization`
+/// headers, as some proxies duplicate them harmlessly
+///
+/// Inside of Rocket's figment these are written in lowercase, e.g. `"first"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum MultiHeaderPolicy {
+    /// Fail with `400 Bad Request` and [BasicAuthError::BadCount](crate::BasicAuthError::BadCount)
+    #[default]
+    Reject,
+
+    /// Use the first header and ignore the rest
+    First,
+
+    /// Use the last header and ignore the rest
+    Last,
+}
+
+/// Policy deciding what the [BasicAuth](crate::BasicAuth) request guard does
+/// when a request has no `Authorization` header
+///
+/// Inside of Rocket's figment these are written in lowercase, e.g. `"fail"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum MissingPolicy {
+    /// Forward with `401 Unauthorized` so other routes can handle the request
+    #[default]
+    Forward,
+
+    /// Fail with `401 Unauthorized` and [BasicAuthError::Missing](crate::BasicAuthError::Missing)
+    /// like [RequiredBasicAuth](crate::RequiredBasicAuth)
+    Fail,
+}
+
+/// Status codes the [BasicAuth](crate::BasicAuth) request guard fails or
+/// forwards with for each failure reason, e.g. to treat malformed credentials
+/// as `401 Unauthorized` or `422 Unprocessable Entity` instead
+///
+/// Failure reasons without an entry keep the status of their
+/// [BasicAuthError]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", default)]
+pub struct StatusPolicy {
+    /// Status for [BasicAuthError::Invalid], [BasicAuthError::BadBase64],
+    /// [BasicAuthError::BadUtf8] and [BasicAuthError::NoSeparator], defaulting
+    /// to `400 Bad Request`, also used by the other request guards like
+    /// [BearerAuth](crate::BearerAuth) when their credentials are malformed
+    pub invalid: u16,
+
+    /// Status for [BasicAuthError::BadCount], defaulting to `400 Bad Request`
+    pub bad_count: u16,
+
+    /// Status for [BasicAuthError::Missing], which is forwarded or failed with
+    /// according to [BasicAuthConfig::missing_policy], defaulting to
+    /// `401 Unauthorized`
+    pub missing: u16,
+}
+
+impl StatusPolicy {
+    /// Gets the status to respond with for the given `error`
+    pub fn status(&self, error: &BasicAuthError) -> Status {
+        match error {
+            BasicAuthError::Invalid
+            | BasicAuthError::BadBase64
+            | BasicAuthError::BadUtf8
+            | BasicAuthError::NoSeparator => Status::new(self.invalid),
+            BasicAuthError::BadCount => Status::new(self.bad_count),
+            BasicAuthError::Missing => Status::new(self.missing),
+            error => Status::from(error),
+        }
+    }
+}
+
+impl Default for StatusPolicy {
+    fn default() -> Self {
+        Self {
+            invalid: Status::from(&BasicAuthError::Invalid).code,
+            bad_count: Status::from(&BasicAuthError::BadCount).code,
+            missing: Status::from(&BasicAuthError::Missing).code,
+        }
+    }
+}
+
+/// Base64 alphabet which credentials are decoded with
+///
+/// Inside of Rocket's figment these are written in snake case, e.g. `"url_safe"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "snake_case")]
+pub enum Base64Alphabet {
+    /// Standard alphabet using `+` and `/`, as required by RFC 7617
+    #[default]
+    Standard,
+
+    /// URL-safe alphabet using `-` and `_`, sent by some non-compliant clients
+    UrlSafe,
+}
+
+/// Strictness of the padding check on base64-encoded credentials
+///
+/// Inside of Rocket's figment these are written in lowercase, e.g. `"strict"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum Base64Padding {
+    /// Accepts credentials with canonical, partial or missing `=` padding, for
+    /// clients which strip it
+    #[default]
+    Lenient,
+
+    /// Only accepts credentials with canonical `=` padding, rejecting tokens
+    /// with it stripped as [BasicAuthError::BadBase64](crate::BasicAuthError::BadBase64)
+    Strict,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::error::ErrorKind;
+    use rocket::figment::providers::{Format, Toml};
+    use rocket::local::blocking::Client;
+
+    #[test]
+    fn figment_config() {
+        let toml = Toml::string(
+            r#"
+            [default.basic_auth]
+            realm = "My Website"
+            max_header_len = 1024
+            multi_header_policy = "last"
+            missing_policy = "fail"
+
+            [default.basic_auth.status_policy]
+            invalid = 422
+            "#,
+        )
+        .nested();
+        let rocket = rocket::custom(rocket::Config::figment().merge(toml))
+            .attach(BasicAuthConfig::fairing());
+        let client = Client::tracked(rocket).unwrap();
+
+        assert_eq!(
+            client.rocket().state::<BasicAuthConfig>(),
+            Some(&BasicAuthConfig {
+                realm: "My Website".to_string(),
+                max_header_len: Some(1024),
+                multi_header_policy: MultiHeaderPolicy::Last,
+                missing_policy: MissingPolicy::Fail,
+                status_policy: StatusPolicy {
+                    invalid: 422,
+                    ..StatusPolicy::default()
+                },
+                ..BasicAuthConfig::default()
+            })
+        );
+    }
+
+    #[test]
+    fn figment_config_invalid() {
+        let figment =
+            rocket::Config::figment().merge(("basic_auth.multi_header_policy", "sometimes"));
+        let rocket = rocket::custom(figment).attach(BasicAuthConfig::fairing());
+        let err = Client::tracked(rocket).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::FailedFairings(_)));
+    }
+
+    #[test]
+    fn config_provider() {
+        let config = BasicAuthConfig {
+            realm: "My Website".to_string(),
+            missing_policy: MissingPolicy::Fail,
+            max_header_len: Some(64),
+            ..BasicAuthConfig::default()
+        };
+        let figment = rocket::Config::figment().join(config.clone());
+        assert_eq!(
+            figment
+                .extract_inner::<BasicAuthConfig>(BasicAuthConfig::KEY)
+                .unwrap(),
+            config
+        );
+        assert_eq!(
+            rocket::figment::Figment::from(BasicAuthConfig::default())
+                .extract_inner::<BasicAuthConfig>(BasicAuthConfig::KEY)
+                .unwrap(),
+            BasicAuthConfig::default()
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! Contains the [EnvCredentials] managed state, the fairing loading it from
+//! environment variables and the [EnvBasicAuth] request guard checking against it
+
+use crate::{constant_time_eq, CredentialVerifier, VerifiedBasicAuth};
+use rocket::fairing::{self, Fairing, Info, Kind};
+use rocket::{Build, Rocket};
+use std::env;
+use std::fmt;
+
+/// A single set of expected credentials read from environment variables when
+/// Rocket ignites, used by the [EnvBasicAuth] request guard
+///
+/// This is managed by attaching an [EnvCredentialsFairing], which fails to
+/// ignite Rocket if either variable is missing so misconfigured apps never
+/// start, rather than failing every request
+pub struct EnvCredentials {
+    username: String,
+    password: String,
+}
+
+impl EnvCredentials {
+    /// Default environment variable holding the expected username
+    pub const USERNAME_VAR: &'static str = "BASIC_AUTH_USER";
+
+    /// Default environment variable holding the expected password
+    pub const PASSWORD_VAR: &'static str = "BASIC_AUTH_PASS";
+
+    /// Reads the expected credentials from the environment variables called
+    /// `username_var` and `password_var`
+    pub fn from_env(username_var: &str, password_var: &str) -> Result<Self, env::VarError> {
+        Ok(Self {
+            username: env::var(username_var)?,
+            password: env::var(password_var)?,
+        })
+    }
+
+    /// Gets the expected username
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+}
+
+impl fmt::Debug for EnvCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EnvCredentials")
+            .field("username", &self.username)
+            .field("password", &"[redacted]")
+            .finish()
+    }
+}
+
+#[rocket::async_trait]
+impl CredentialVerifier for EnvCredentials {
+    async fn verify(&self, username: &str, password: &str) -> bool {
+        // non-short-circuiting `&` so the password is always compared
+        constant_time_eq(&self.username, username) & constant_time_eq(&self.password, password)
+    }
+}
+
+/// A fairing reading [EnvCredentials] from environment variables once Rocket
+/// ignites, defaulting to [EnvCredentials::USERNAME_VAR] and
+/// [EnvCredentials::PASSWORD_VAR], and failing to ignite if either is missing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvCredentialsFairing {
+    username_var: String,
+    password_var: String,
+}
+
+impl EnvCredentialsFairing {
+    /// Creates a new fairing reading the expected credentials from the
+    /// environment variables called `username_var` and `password_var`
+    pub fn new<T: Into<String>>(username_var: T, password_var: T) -> Self {
+        Self {
+            username_var: username_var.into(),
+            password_var: password_var.into(),
+        }
+    }
+}
+
+impl Default for EnvCredentialsFairing {
+    fn default() -> Self {
+        Self::new(EnvCredentials::USERNAME_VAR, EnvCredentials::PASSWORD_VAR)
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for EnvCredentialsFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Basic Authentication Environment Credentials",
+            kind: Kind::Ignite,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        match EnvCredentials::from_env(&self.username_var, &self.password_var) {
+            Ok(credentials) => Ok(rocket.manage(credentials)),
+            Err(_err) => {
+                #[cfg(feature = "log")]
+                log::error!(
+                    "Expected basic authentication credentials inside of the {} and {} environment variables: {}",
+                    self.username_var,
+                    self.password_var,
+                    _err
+                );
+                Err(rocket)
+            }
+        }
+    }
+}
+
+/// A request guard containing credentials which match the [EnvCredentials]
+/// inside of Rocket's managed state, see [VerifiedBasicAuth] for its behaviour
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::{EnvBasicAuth, EnvCredentialsFairing};
+///
+/// #[get("/")]
+/// fn index(auth: EnvBasicAuth) -> String {
+///     format!("Welcome back, {}!", auth.username)
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     // reads `BASIC_AUTH_USER` and `BASIC_AUTH_PASS`
+///     rocket::build()
+///         .attach(EnvCredentialsFairing::default())
+///         .mount("/", routes![index])
+/// }
+/// ```
+pub type EnvBasicAuth = VerifiedBasicAuth<EnvCredentials>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::error::ErrorKind;
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+    use std::sync::Mutex;
+
+    /// Serializes tests modifying environment variables
+    static ENV: Mutex<()> = Mutex::new(());
+
+    #[get("/")]
+    fn index(auth: EnvBasicAuth) -> String {
+        auth.username.clone()
+    }
+
+    #[test]
+    fn env_guard() {
+        let _env = ENV.lock().unwrap();
+        env::set_var("ROCKET_BASICAUTH_TEST_USER", "name");
+        env::set_var("ROCKET_BASICAUTH_TEST_PASS", "password");
+
+        let rocket = rocket::build()
+            .attach(EnvCredentialsFairing::new(
+                "ROCKET_BASICAUTH_TEST_USER",
+                "ROCKET_BASICAUTH_TEST_PASS",
+            ))
+            .mount("/", routes![index]);
+        let client = Client::tracked(rocket).unwrap();
+        // read once on ignite, so later changes don't apply
+        env::set_var("ROCKET_BASICAUTH_TEST_PASS", "changed");
+        let get = |header: &'static str| {
+            client
+                .get("/")
+                .header(Header::new("Authorization", header))
+                .dispatch()
+                .status()
+        };
+
+        // Tests: name:password
+        assert_eq!(get("Basic bmFtZTpwYXNzd29yZA=="), Status::Ok);
+        // Tests: name:changed
+        assert_eq!(get("Basic bmFtZTpjaGFuZ2Vk"), Status::Unauthorized);
+
+        env::remove_var("ROCKET_BASICAUTH_TEST_USER");
+        env::remove_var("ROCKET_BASICAUTH_TEST_PASS");
+    }
+
+    #[test]
+    fn env_missing() {
+        let _env = ENV.lock().unwrap();
+        env::set_var("ROCKET_BASICAUTH_TEST_MISSING_USER", "name");
+
+        let rocket = rocket::build().attach(EnvCredentialsFairing::new(
+            "ROCKET_BASICAUTH_TEST_MISSING_USER",
+            "ROCKET_BASICAUTH_TEST_MISSING_PASS",
+        ));
+        let err = Client::tracked(rocket).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::FailedFairings(_)));
+
+        env::remove_var("ROCKET_BASICAUTH_TEST_MISSING_USER");
+    }
+}
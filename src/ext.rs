@@ -0,0 +1,53 @@
+//! Contains the [BasicAuthRocketExt] extension trait for quickly protecting
+//! areas of an app
+
+use crate::{BasicAuthFairing, BasicAuthStore};
+use rocket::{Build, Rocket};
+
+/// Extension methods on [Rocket] for protecting areas of an app with basic
+/// authentication in a single line
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::{BasicAuthRocketExt, BasicAuthStore};
+///
+/// #[get("/")]
+/// fn index() -> &'static str {
+///     "Visible to everyone"
+/// }
+///
+/// #[get("/")]
+/// fn admin() -> &'static str {
+///     "Only visible with valid credentials"
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let mut store = BasicAuthStore::new();
+///     store.insert("alice", "wonderland");
+///
+///     rocket::build()
+///         .mount("/", routes![index])
+///         .mount("/admin", routes![admin])
+///         .protect("/admin", store)
+/// }
+/// ```
+pub trait BasicAuthRocketExt {
+    /// Requires credentials from the `store` for every request to a path
+    /// starting with `base_path`, e.g. `/admin` and `/admin/users` but not
+    /// `/administrator`, leaving the rest of the app open
+    ///
+    /// This attaches a [BasicAuthFairing] limited to `base_path` using
+    /// [BasicAuthFairing::within], so unauthorized requests get a
+    /// `401 Unauthorized` challenge without having to register a catcher
+    fn protect(self, base_path: &str, store: BasicAuthStore) -> Self;
+}
+
+impl BasicAuthRocketExt for Rocket<Build> {
+    fn protect(self, base_path: &str, store: BasicAuthStore) -> Self {
+        self.attach(BasicAuthFairing::new(store).within(base_path))
+    }
+}
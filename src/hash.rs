@@ -0,0 +1,103 @@
+//! Contains password hash verification for the `hash` feature
+
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use std::convert::TryFrom;
+
+/// Salt used by [hash_like] for argon2 hashes
+const DUMMY_SALT: &[u8] = b"rocketbasicauth!";
+
+/// Checks a plaintext `password` against a stored password hash, detecting if
+/// it's an argon2 [PHC string](https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md)
+/// or a bcrypt hash from its prefix
+///
+/// Any hash which fails to parse or uses an unknown algorithm is treated as a
+/// mismatch
+pub(crate) fn verify_password_hash(password: &str, hash: &str) -> bool {
+    if hash.starts_with("$argon2") {
+        match PasswordHash::new(hash) {
+            Ok(parsed) => Argon2::default()
+                .verify_password(password.as_bytes(), &parsed)
+                .is_ok(),
+            Err(_) => false,
+        }
+    } else if is_bcrypt(hash) {
+        bcrypt::verify(password, hash).unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+/// Hashes a `password` using the same algorithm and parameters, such as the
+/// bcrypt cost, as the existing argon2 or bcrypt `hash`, so verifying against
+/// either takes as long
+///
+/// Hashes which fail to parse are returned as-is, as verifying against them
+/// fails just as quickly
+pub(crate) fn hash_like(password: &str, hash: &str) -> String {
+    let hashed = if hash.starts_with("$argon2") {
+        PasswordHash::new(hash).ok().and_then(|parsed| {
+            let algorithm = Algorithm::try_from(parsed.algorithm).ok()?;
+            let version = match parsed.version {
+                Some(version) => Version::try_from(version).ok()?,
+                None => Version::default(),
+            };
+            let params = Params::try_from(&parsed).ok()?;
+            let salt = SaltString::encode_b64(DUMMY_SALT).ok()?;
+            let hashed = Argon2::new(algorithm, version, params)
+                .hash_password(password.as_bytes(), &salt)
+                .ok()?;
+            Some(hashed.to_string())
+        })
+    } else if is_bcrypt(hash) {
+        hash.get(4..6)
+            .and_then(|cost| cost.parse().ok())
+            .and_then(|cost| bcrypt::hash(password, cost).ok())
+    } else {
+        None
+    };
+    hashed.unwrap_or_else(|| hash.to_string())
+}
+
+/// Checks if a `hash` is a bcrypt hash from its prefix
+fn is_bcrypt(hash: &str) -> bool {
+    ["$2a$", "$2b$", "$2x$", "$2y$"]
+        .iter()
+        .any(|prefix| hash.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Argon2id hash of `hunter2`
+    const ARGON2ID: &str =
+        "$argon2id$v=19$m=64,t=1,p=1$cm9ja2V0YmFzaWNhdXRoIQ$T3/QAAWc0Xysg0DsfkwB7SaKwSfZev/qmpTJ9x5/cjI";
+
+    /// Bcrypt hash of `hunter2`
+    const BCRYPT: &str = "$2b$04$ak7hY0TyWkDxYULfbVPmGOfL3xGvfln9820rKJSc3ayrbNMuINe6C";
+
+    #[test]
+    fn verify_password_hash_check() {
+        assert!(verify_password_hash("hunter2", ARGON2ID));
+        assert!(!verify_password_hash("hunter3", ARGON2ID));
+        assert!(verify_password_hash("hunter2", BCRYPT));
+        assert!(!verify_password_hash("hunter3", BCRYPT));
+        assert!(!verify_password_hash("hunter2", "$argon2id$garbage"));
+        assert!(!verify_password_hash("hunter2", "$2b$garbage"));
+        assert!(!verify_password_hash("hunter2", "hunter2"));
+        assert!(!verify_password_hash("hunter2", ""));
+    }
+
+    #[test]
+    fn hash_like_check() {
+        let argon2id = hash_like("wonderland", ARGON2ID);
+        assert!(argon2id.starts_with("$argon2id$v=19$m=64,t=1,p=1$"));
+        assert!(verify_password_hash("wonderland", &argon2id));
+        let bcrypt = hash_like("wonderland", BCRYPT);
+        assert!(bcrypt.starts_with("$2b$04$"));
+        assert!(verify_password_hash("wonderland", &bcrypt));
+        assert_eq!(hash_like("wonderland", "$2b$garbage"), "$2b$garbage");
+        assert_eq!(hash_like("wonderland", "hunter2"), "hunter2");
+    }
+}
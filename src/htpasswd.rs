@@ -0,0 +1,211 @@
+//! Contains parsing and verification of Apache `.htpasswd` files, available
+//! with the `htpasswd` feature
+
+use crate::constant_time_eq;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use md5::{Digest, Md5};
+use sha1::Sha1;
+
+/// Alphabet used by the hashes of apr1 entries
+const ITOA64: &[u8] = b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Parses the lines of a `.htpasswd` file into `(username, hash)` pairs,
+/// skipping blank lines, comments and entries in unsupported formats
+pub(crate) fn parse(contents: &str) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+
+    for (ind, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match line.split_once(':') {
+            Some((username, hash)) if is_supported(hash) => {
+                entries.push((username.to_string(), hash.to_string()))
+            }
+            _ => {
+                #[cfg(feature = "log")]
+                log::warn!("Skipping unsupported .htpasswd entry on line {}", ind + 1);
+                #[cfg(not(feature = "log"))]
+                let _ = ind;
+            }
+        }
+    }
+
+    entries
+}
+
+/// Checks if a `.htpasswd` hash is in a format which can be verified
+fn is_supported(hash: &str) -> bool {
+    ["$2a$", "$2b$", "$2x$", "$2y$", "$apr1$", "{SHA}"]
+        .iter()
+        .any(|prefix| hash.starts_with(prefix))
+}
+
+/// Checks if a `password` matches a `.htpasswd` hash, using bcrypt, apr1 or
+/// SHA depending on its prefix
+pub(crate) fn verify(password: &str, hash: &str) -> bool {
+    if let Some(digest) = hash.strip_prefix("{SHA}") {
+        constant_time_eq(&STANDARD.encode(Sha1::digest(password)), digest)
+    } else if let Some(rest) = hash.strip_prefix("$apr1$") {
+        match rest.split_once('$') {
+            Some((salt, _)) => constant_time_eq(&apr1(password, salt), hash),
+            None => false,
+        }
+    } else if is_supported(hash) {
+        bcrypt::verify(password, hash).unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+/// Hashes a `password` using the same format and parameters, such as the
+/// bcrypt cost, as the existing `.htpasswd` `hash`, so verifying against either
+/// takes as long
+///
+/// Hashes in unsupported formats are returned as-is, as verifying against them
+/// fails just as quickly
+pub(crate) fn hash_like(password: &str, hash: &str) -> String {
+    let hashed = if hash.starts_with("{SHA}") {
+        Some(format!(
+            "{{SHA}}{}",
+            STANDARD.encode(Sha1::digest(password))
+        ))
+    } else if let Some(rest) = hash.strip_prefix("$apr1$") {
+        rest.split_once('$').map(|(salt, _)| apr1(password, salt))
+    } else if is_supported(hash) {
+        hash.get(4..6)
+            .and_then(|cost| cost.parse().ok())
+            .and_then(|cost| bcrypt::hash(password, cost).ok())
+    } else {
+        None
+    };
+    hashed.unwrap_or_else(|| hash.to_string())
+}
+
+/// Hashes a `password` with Apache's apr1 variant of md5-crypt using the
+/// given `salt`, returning the full `$apr1$salt$hash` string
+fn apr1(password: &str, salt: &str) -> String {
+    const MAGIC: &str = "$apr1$";
+    let password = password.as_bytes();
+    let salt = &salt.as_bytes()[..salt.len().min(8)];
+
+    let mut alternate = Md5::new();
+    alternate.update(password);
+    alternate.update(salt);
+    alternate.update(password);
+    let alternate = alternate.finalize();
+
+    let mut ctx = Md5::new();
+    ctx.update(password);
+    ctx.update(MAGIC);
+    ctx.update(salt);
+    for chunk in password.chunks(16) {
+        ctx.update(&alternate[..chunk.len()]);
+    }
+    let mut len = password.len();
+    while len > 0 {
+        if len & 1 == 1 {
+            ctx.update([0]);
+        } else {
+            ctx.update(&password[..1]);
+        }
+        len >>= 1;
+    }
+    let mut digest = ctx.finalize();
+
+    for round in 0..1000 {
+        let mut ctx = Md5::new();
+        if round & 1 == 1 {
+            ctx.update(password);
+        } else {
+            ctx.update(digest);
+        }
+        if round % 3 != 0 {
+            ctx.update(salt);
+        }
+        if round % 7 != 0 {
+            ctx.update(password);
+        }
+        if round & 1 == 1 {
+            ctx.update(digest);
+        } else {
+            ctx.update(password);
+        }
+        digest = ctx.finalize();
+    }
+
+    let mut encoded = String::with_capacity(22);
+    let mut push = |value: u32, chars: usize| {
+        let mut value = value;
+        for _ in 0..chars {
+            encoded.push(ITOA64[(value & 0x3f) as usize] as char);
+            value >>= 6;
+        }
+    };
+    for (a, b, c) in [(0, 6, 12), (1, 7, 13), (2, 8, 14), (3, 9, 15), (4, 10, 5)] {
+        push(
+            (digest[a] as u32) << 16 | (digest[b] as u32) << 8 | digest[c] as u32,
+            4,
+        );
+    }
+    push(digest[11] as u32, 2);
+
+    format!("{}{}${}", MAGIC, String::from_utf8_lossy(salt), encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apr1_check() {
+        assert_eq!(
+            apr1("myPassword", "r31....."),
+            "$apr1$r31.....$HqJZimcKQFAMYayBlzkrA/"
+        );
+    }
+
+    #[test]
+    fn verify_check() {
+        assert!(verify("myPassword", "{SHA}VBPuJHI7uixaa6LQGWx4s+5GKNE="));
+        assert!(!verify("wrong", "{SHA}VBPuJHI7uixaa6LQGWx4s+5GKNE="));
+        assert!(verify(
+            "myPassword",
+            "$apr1$r31.....$HqJZimcKQFAMYayBlzkrA/"
+        ));
+        assert!(!verify("wrong", "$apr1$r31.....$HqJZimcKQFAMYayBlzkrA/"));
+        assert!(!verify("myPassword", "$apr1$r31....."));
+        assert!(!verify("myPassword", "myPassword"));
+    }
+
+    #[test]
+    fn hash_like_check() {
+        let sha = hash_like("wonderland", "{SHA}VBPuJHI7uixaa6LQGWx4s+5GKNE=");
+        assert!(verify("wonderland", &sha));
+        let apr1 = hash_like("wonderland", "$apr1$r31.....$HqJZimcKQFAMYayBlzkrA/");
+        assert!(apr1.starts_with("$apr1$r31.....$"));
+        assert!(verify("wonderland", &apr1));
+        let bcrypt = hash_like(
+            "wonderland",
+            "$2y$04$ak7hY0TyWkDxYULfbVPmGOfL3xGvfln9820rKJSc3ayrbNMuINe6C",
+        );
+        assert!(bcrypt.starts_with("$2b$04$"));
+        assert!(verify("wonderland", &bcrypt));
+        assert_eq!(hash_like("wonderland", "$apr1$r31....."), "$apr1$r31.....");
+    }
+
+    #[test]
+    fn parse_check() {
+        let entries = parse("# comment\n\nalice:{SHA}abc\nbob:plaintext\n carol:$apr1$x$y \n");
+        assert_eq!(
+            entries,
+            vec![
+                ("alice".to_string(), "{SHA}abc".to_string()),
+                ("carol".to_string(), "$apr1$x$y".to_string())
+            ]
+        );
+    }
+}
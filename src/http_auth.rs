@@ -0,0 +1,132 @@
+//! Contains the [HttpAuth] request guard accepting multiple auth schemes
+
+use crate::{
+    authorization_header, invalid_credentials, strip_scheme, BasicAuth, BasicAuthConfig,
+    BasicAuthError, BearerAuth,
+};
+use rocket::outcome::{try_outcome, Outcome};
+use rocket::request::{self, FromRequest, Request};
+
+/// A request guard accepting either [basic access authentication](BasicAuth)
+/// or a [bearer token](BearerAuth), dispatching on the scheme of the
+/// `Authorization` header so that a route can `match` on which was used
+///
+/// Schemes are matched case-insensitively, and any other scheme fails with
+/// `400 Bad Request` and [BasicAuthError::Invalid]
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::HttpAuth;
+///
+/// #[get("/")]
+/// fn index(auth: HttpAuth) -> String {
+///     match auth {
+///         HttpAuth::Basic(auth) => format!("Hello, {}!", auth.username),
+///         HttpAuth::Bearer(token) => format!("Your token is {}", token),
+///     }
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().mount("/", routes![index])
+/// }
+/// ```
+#[derive(Debug)]
+pub enum HttpAuth {
+    /// Basic access authentication credentials
+    Basic(BasicAuth),
+
+    /// Raw bearer token
+    Bearer(String),
+}
+
+impl HttpAuth {
+    /// Creates a new [HttpAuth] struct/request guard from a given plaintext
+    /// http auth header or returns a [Option::None] if invalid or using an
+    /// unknown scheme
+    pub fn new<T: Into<String>>(auth_header: T) -> Option<Self> {
+        Self::with_config(auth_header, &BasicAuthConfig::default())
+    }
+
+    /// Creates a new [HttpAuth] struct/request guard like [HttpAuth::new],
+    /// decoding basic credentials according to the given `config` instead of
+    /// the defaults
+    pub fn with_config<T: Into<String>>(auth_header: T, config: &BasicAuthConfig) -> Option<Self> {
+        let key = auth_header.into();
+
+        if strip_scheme(&key, "Basic").is_some() {
+            BasicAuth::with_config(key, config).map(HttpAuth::Basic)
+        } else if strip_scheme(&key, "Bearer").is_some() {
+            BearerAuth::new(key).map(|bearer| HttpAuth::Bearer(bearer.0))
+        } else {
+            None
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for HttpAuth {
+    type Error = BasicAuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let key = try_outcome!(authorization_header(request));
+        match HttpAuth::with_config(key, BasicAuthConfig::from_request(request)) {
+            Some(auth) => Outcome::Success(auth),
+            None => invalid_credentials(request),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+
+    #[get("/")]
+    fn index(auth: HttpAuth) -> String {
+        match auth {
+            HttpAuth::Basic(auth) => format!("basic {}", auth.username),
+            HttpAuth::Bearer(token) => format!("bearer {}", token),
+        }
+    }
+
+    #[test]
+    fn http_auth_guard() {
+        let client = Client::tracked(rocket::build().mount("/", routes![index])).unwrap();
+        let get = |header: &'static str| {
+            let resp = client
+                .get("/")
+                .header(Header::new("Authorization", header))
+                .dispatch();
+            (resp.status(), resp.into_string())
+        };
+
+        // Tests: name:password
+        assert_eq!(get("BASIC bmFtZTpwYXNzd29yZA==").1.unwrap(), "basic name");
+        assert_eq!(get("bearer abc.def-123").1.unwrap(), "bearer abc.def-123");
+        // Tests: name:password, with leading whitespace
+        assert_eq!(
+            get(" \tBasic bmFtZTpwYXNzd29yZA==").1.unwrap(),
+            "basic name"
+        );
+        assert!(matches!(
+            HttpAuth::new(" Basic bmFtZTpwYXNzd29yZA=="),
+            Some(HttpAuth::Basic(auth)) if auth.username == "name"
+        ));
+        assert!(matches!(
+            HttpAuth::new(" Bearer abc"),
+            Some(HttpAuth::Bearer(token)) if token == "abc"
+        ));
+        assert_eq!(
+            get("Digest username=\"name\", realm=\"x\"").0,
+            Status::BadRequest
+        );
+        assert_eq!(get("Basic !!!").0, Status::BadRequest);
+        assert_eq!(client.get("/").dispatch().status(), Status::Unauthorized);
+    }
+}
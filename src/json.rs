@@ -0,0 +1,122 @@
+//! Contains the JSON [Responder] for [BasicAuthError], available with the
+//! `json` feature
+
+use crate::{BasicAuthChallenge, BasicAuthError};
+use rocket::http::Status;
+use rocket::request::Request;
+use rocket::response::{self, Responder, Response};
+use rocket::serde::json::{json, Json};
+
+impl BasicAuthError {
+    /// Machine-readable code for this error sent inside of JSON responses
+    fn code(&self) -> &'static str {
+        match self {
+            BasicAuthError::BadCount => "bad_count",
+            BasicAuthError::Missing => "missing_credentials",
+            BasicAuthError::Invalid => "invalid_credentials",
+            BasicAuthError::BadBase64 => "invalid_base64",
+            BasicAuthError::BadUtf8 => "invalid_utf8",
+            BasicAuthError::NoSeparator => "missing_separator",
+            BasicAuthError::TooLarge => "header_too_large",
+            BasicAuthError::Unmanaged => "unmanaged_state",
+            BasicAuthError::Locked => "locked_out",
+            BasicAuthError::Insecure => "insecure_connection",
+            BasicAuthError::Unavailable => "store_unavailable",
+            BasicAuthError::Forbidden => "forbidden",
+        }
+    }
+}
+
+/// Responds with the error's [Status] and a JSON body such as
+/// `{"error":"invalid_credentials","detail":"..."}`, available with the `json`
+/// feature
+///
+/// `401 Unauthorized` responses also carry a `WWW-Authenticate` header like a
+/// [BasicAuthChallenge] so browsers still prompt for credentials
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::{BasicAuth, BasicAuthError};
+///
+/// #[get("/")]
+/// fn index(auth: Result<BasicAuth, BasicAuthError>) -> Result<String, BasicAuthError> {
+///     Ok(format!("Hello, {}!", auth?.username))
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().mount("/", routes![index])
+/// }
+/// ```
+impl<'r> Responder<'r, 'static> for BasicAuthError {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let status = Status::from(&self);
+        let body = Json(json!({
+            "error": self.code(),
+            "detail": self.to_string(),
+        }));
+
+        let mut response = Response::build_from(body.respond_to(request)?);
+        response.status(status);
+        if status == Status::Unauthorized {
+            response.raw_header(
+                "WWW-Authenticate",
+                BasicAuthChallenge::default().request_header_value(request),
+            );
+        }
+        response.ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BasicAuth;
+    use rocket::http::{ContentType, Header};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+
+    #[get("/")]
+    fn index(auth: Result<BasicAuth, BasicAuthError>) -> Result<String, BasicAuthError> {
+        Ok(auth?.username.clone())
+    }
+
+    #[get("/missing")]
+    fn missing() -> BasicAuthError {
+        BasicAuthError::Missing
+    }
+
+    #[test]
+    fn json_responder() {
+        let client = Client::tracked(rocket::build().mount("/", routes![index, missing])).unwrap();
+
+        let resp = client
+            .get("/")
+            .header(Header::new("Authorization", "Basic !!!"))
+            .dispatch();
+        assert_eq!(resp.status(), Status::BadRequest);
+        assert_eq!(resp.content_type(), Some(ContentType::JSON));
+        assert_eq!(resp.headers().get_one("WWW-Authenticate"), None);
+        let body: serde_json::Value = serde_json::from_str(&resp.into_string().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "error": "invalid_base64",
+                "detail": "bad base64: credentials are not validly base64-encoded",
+            })
+        );
+
+        let resp = client.get("/missing").dispatch();
+        assert_eq!(resp.status(), Status::Unauthorized);
+        assert_eq!(resp.content_type(), Some(ContentType::JSON));
+        assert_eq!(
+            resp.headers().get_one("WWW-Authenticate"),
+            Some("Basic realm=\"Restricted\"")
+        );
+        let body: serde_json::Value = serde_json::from_str(&resp.into_string().unwrap()).unwrap();
+        assert_eq!(body["error"], "missing_credentials");
+    }
+}
@@ -0,0 +1,142 @@
+//! Contains the [UserLoader] trait, its [Users] managed state and the
+//! [AuthenticatedUser] request guard which yields the loaded user
+
+use crate::{BasicAuth, BasicAuthError, FailureTracker};
+use rocket::http::Status;
+use rocket::outcome::{try_outcome, Outcome};
+use rocket::request::{self, FromRequest, Request};
+use std::fmt;
+use std::ops::Deref;
+
+/// Loads users of type `U` by their credentials, such as from a database, used
+/// by the [AuthenticatedUser] request guard
+///
+/// Loaders are retrieved from Rocket's managed state wrapped inside of a
+/// [Users], so make sure to [manage](rocket::Rocket::manage) one when building
+/// your app. As this trait is asynchronous, implementations need the
+/// [rocket::async_trait] attribute
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::{AuthenticatedUser, UserLoader, Users};
+/// use std::collections::HashMap;
+///
+/// struct User {
+///     name: String,
+///     admin: bool,
+/// }
+///
+/// /// Simple loader holding plaintext passwords in memory
+/// struct InMemoryLoader {
+///     users: HashMap<String, (String, bool)>,
+/// }
+///
+/// #[rocket::async_trait]
+/// impl UserLoader<User> for InMemoryLoader {
+///     async fn load(&self, username: &str, password: &str) -> Option<User> {
+///         let (expected, admin) = self.users.get(username)?;
+///         (expected == password).then(|| User { name: username.to_string(), admin: *admin })
+///     }
+/// }
+///
+/// #[get("/")]
+/// fn index(user: AuthenticatedUser<User>) -> String {
+///     format!("Welcome back, {}! Admin: {}", user.name, user.admin)
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let mut users = HashMap::new();
+///     users.insert("alice".to_string(), ("wonderland".to_string(), true));
+///
+///     rocket::build()
+///         .manage(Users::new(InMemoryLoader { users }))
+///         .mount("/", routes![index])
+/// }
+/// ```
+#[rocket::async_trait]
+pub trait UserLoader<U>: Send + Sync + 'static {
+    /// Loads the user with the given `username` if the `password` is theirs,
+    /// returning [Option::None] if the credentials are invalid
+    async fn load(&self, username: &str, password: &str) -> Option<U>;
+}
+
+/// Managed state holding the [UserLoader] which [AuthenticatedUser] guards
+/// load users of type `U` with, see [UserLoader] for an example
+pub struct Users<U>(Box<dyn UserLoader<U>>);
+
+impl<U> Users<U> {
+    /// Creates new managed state loading users with the given `loader`
+    pub fn new<L: UserLoader<U>>(loader: L) -> Self {
+        Self(Box::new(loader))
+    }
+}
+
+impl<U> fmt::Debug for Users<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Users").finish_non_exhaustive()
+    }
+}
+
+/// A request guard containing the user of type `U` which the [UserLoader]
+/// inside of Rocket's [Users] managed state loaded for the request's
+/// [BasicAuth] credentials, which it dereferences into
+///
+/// This merges checking credentials and loading the user they belong to, so
+/// routes don't have to fetch the user again. Requests with credentials the
+/// loader rejects are forwarded with a `401 Unauthorized` status just like
+/// requests missing them entirely, whilst [Users] missing from managed state
+/// fails with `500 Internal Server Error` and [BasicAuthError::Unmanaged]
+///
+/// See [UserLoader] for a full example
+pub struct AuthenticatedUser<U>(pub U);
+
+impl<U> AuthenticatedUser<U> {
+    /// Unwraps the loaded user
+    pub fn into_inner(self) -> U {
+        self.0
+    }
+}
+
+impl<U> Deref for AuthenticatedUser<U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        &self.0
+    }
+}
+
+impl<U: fmt::Debug> fmt::Debug for AuthenticatedUser<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AuthenticatedUser").field(&self.0).finish()
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, U: Send + 'static> FromRequest<'r> for AuthenticatedUser<U> {
+    type Error = BasicAuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let users = match request.rocket().state::<Users<U>>() {
+            Some(users) => users,
+            None => {
+                return Outcome::Error((Status::InternalServerError, BasicAuthError::Unmanaged))
+            }
+        };
+        let auth = try_outcome!(BasicAuth::from_request(request).await);
+
+        match users.0.load(&auth.username, &auth.password).await {
+            Some(user) => {
+                FailureTracker::request_succeeded(request);
+                Outcome::Success(Self(user))
+            }
+            None => {
+                FailureTracker::request_failed(request);
+                Outcome::Forward(Status::Unauthorized)
+            }
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! Contains the [FailureTracker] managed state which locks clients out after
+//! repeated failed authentication attempts
+
+use rocket::request::Request;
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Source of the current time used by a [FailureTracker] or
+/// [CachedVerifier](crate::CachedVerifier), which can be replaced to control
+/// time inside of tests
+pub trait Clock: Send + Sync + 'static {
+    /// Gets the current instant
+    fn now(&self) -> Instant;
+}
+
+/// The default [Clock], using [Instant::now]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Failed attempts made by a single client within the current window
+#[derive(Debug, Clone, Copy)]
+struct Failures {
+    count: u32,
+    since: Instant,
+}
+
+/// Managed state recording failed authentication attempts per client IP,
+/// locking a client out once it fails `threshold` times within a `window`
+///
+/// Whilst locked out, [BasicAuth](crate::BasicAuth) and the guards built on it
+/// fail with `429 Too Many Requests` and [BasicAuthError::Locked](crate::BasicAuthError::Locked)
+/// until the window which started with the client's first failure elapses.
+/// Badly formatted credentials count as failures, as do credentials rejected
+/// by guards which check them such as [FixedBasicAuth](crate::FixedBasicAuth)
+/// and [VerifiedBasicAuth](crate::VerifiedBasicAuth), whose successes reset the
+/// client's count. Requests without a known client IP are never tracked
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::{BasicAuthStore, FailureTracker, StoreBasicAuth};
+/// use std::time::Duration;
+///
+/// #[get("/")]
+/// fn index(auth: StoreBasicAuth) -> String {
+///     format!("Welcome back, {}!", auth.username)
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let mut store = BasicAuthStore::new();
+///     store.insert("alice", "wonderland");
+///
+///     rocket::build()
+///         .manage(store)
+///         .manage(FailureTracker::new(5, Duration::from_secs(60)))
+///         .mount("/", routes![index])
+/// }
+/// ```
+pub struct FailureTracker {
+    threshold: u32,
+    window: Duration,
+    clock: Box<dyn Clock>,
+    failures: Mutex<HashMap<IpAddr, Failures>>,
+}
+
+impl FailureTracker {
+    /// Creates a new tracker locking clients out after `threshold` failures
+    /// within the given `window`
+    pub fn new(threshold: u32, window: Duration) -> Self {
+        Self {
+            threshold,
+            window,
+            clock: Box::new(SystemClock),
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Replaces the [Clock] used to measure windows, which is [SystemClock] by
+    /// default
+    pub fn with_clock<C: Clock>(mut self, clock: C) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Checks if the client with the given `ip` is currently locked out
+    pub fn is_locked(&self, ip: IpAddr) -> bool {
+        let now = self.clock.now();
+        match self.failures.lock().unwrap().get(&ip) {
+            Some(failures) => failures.count >= self.threshold && !self.expired(failures, now),
+            None => false,
+        }
+    }
+
+    /// Records a failed attempt from the client with the given `ip`
+    pub fn record_failure(&self, ip: IpAddr) {
+        let now = self.clock.now();
+        let mut failures = self.failures.lock().unwrap();
+        failures.retain(|_, failures| !self.expired(failures, now));
+        failures
+            .entry(ip)
+            .or_insert(Failures {
+                count: 0,
+                since: now,
+            })
+            .count += 1;
+    }
+
+    /// Forgets all failed attempts from the client with the given `ip`
+    pub fn reset(&self, ip: IpAddr) {
+        self.failures.lock().unwrap().remove(&ip);
+    }
+
+    /// Checks if the window for a client's `failures` has elapsed at `now`
+    fn expired(&self, failures: &Failures, now: Instant) -> bool {
+        now.saturating_duration_since(failures.since) >= self.window
+    }
+
+    /// Checks if the client making the `request` is locked out by the tracker
+    /// if one is managed
+    pub(crate) fn is_request_locked(request: &Request<'_>) -> bool {
+        match (request.rocket().state::<Self>(), request.client_ip()) {
+            (Some(tracker), Some(ip)) => tracker.is_locked(ip),
+            _ => false,
+        }
+    }
+
+    /// Records a failed attempt for the client making the `request` if a
+    /// tracker is managed
+    pub(crate) fn request_failed(request: &Request<'_>) {
+        if let (Some(tracker), Some(ip)) = (request.rocket().state::<Self>(), request.client_ip()) {
+            tracker.record_failure(ip);
+        }
+    }
+
+    /// Resets the client making the `request` if a tracker is managed
+    pub(crate) fn request_succeeded(request: &Request<'_>) {
+        if let (Some(tracker), Some(ip)) = (request.rocket().state::<Self>(), request.client_ip()) {
+            tracker.reset(ip);
+        }
+    }
+}
+
+impl fmt::Debug for FailureTracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FailureTracker")
+            .field("threshold", &self.threshold)
+            .field("window", &self.window)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BasicAuth, BasicAuthError, FixedBasicAuth, FixedCredentials};
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+    use std::sync::Arc;
+
+    #[derive(Clone)]
+    struct TestClock(Arc<Mutex<Instant>>);
+
+    impl TestClock {
+        fn advance(&self, duration: Duration) {
+            *self.0.lock().unwrap() += duration;
+        }
+    }
+
+    impl Clock for TestClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    struct Admin;
+
+    impl FixedCredentials for Admin {
+        const USERNAME: &'static str = "admin";
+        const PASSWORD: &'static str = "hunter2";
+    }
+
+    #[get("/")]
+    fn index(auth: BasicAuth) -> String {
+        auth.username.clone()
+    }
+
+    #[get("/admin")]
+    fn admin(_auth: FixedBasicAuth<Admin>) -> &'static str {
+        "admin"
+    }
+
+    fn client(clock: &TestClock) -> Client {
+        let tracker = FailureTracker::new(3, Duration::from_secs(60)).with_clock(clock.clone());
+        let rocket = rocket::build()
+            .manage(tracker)
+            .mount("/", routes![index, admin]);
+        Client::tracked(rocket).unwrap()
+    }
+
+    fn status(client: &Client, path: &'static str, header: &'static str) -> Status {
+        client
+            .get(path)
+            .remote("192.0.2.1:8000".parse().unwrap())
+            .header(Header::new("Authorization", header))
+            .dispatch()
+            .status()
+    }
+
+    #[test]
+    fn tracker_check() {
+        let clock = TestClock(Arc::new(Mutex::new(Instant::now())));
+        let tracker = FailureTracker::new(2, Duration::from_secs(60)).with_clock(clock.clone());
+        let ip: IpAddr = "192.0.2.1".parse().unwrap();
+        let other: IpAddr = "192.0.2.2".parse().unwrap();
+
+        tracker.record_failure(ip);
+        assert!(!tracker.is_locked(ip));
+        tracker.record_failure(ip);
+        assert!(tracker.is_locked(ip));
+        assert!(!tracker.is_locked(other));
+        tracker.reset(ip);
+        assert!(!tracker.is_locked(ip));
+
+        tracker.record_failure(ip);
+        clock.advance(Duration::from_secs(61));
+        tracker.record_failure(ip);
+        assert!(!tracker.is_locked(ip));
+    }
+
+    #[test]
+    fn lockout_trip_and_clear() {
+        let clock = TestClock(Arc::new(Mutex::new(Instant::now())));
+        let client = client(&clock);
+
+        for _ in 0..3 {
+            assert_eq!(status(&client, "/", "Basic !!!"), Status::BadRequest);
+        }
+        // Tests: name:password
+        assert_eq!(
+            status(&client, "/", "Basic bmFtZTpwYXNzd29yZA=="),
+            Status::TooManyRequests
+        );
+        let req = client.get("/").remote("192.0.2.1:8000".parse().unwrap());
+        assert!(matches!(
+            BasicAuth::try_from_request(req.inner()),
+            Err(BasicAuthError::Locked)
+        ));
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(
+            status(&client, "/", "Basic bmFtZTpwYXNzd29yZA=="),
+            Status::TooManyRequests
+        );
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(
+            status(&client, "/", "Basic bmFtZTpwYXNzd29yZA=="),
+            Status::Ok
+        );
+    }
+
+    #[test]
+    fn lockout_reset_on_success() {
+        let clock = TestClock(Arc::new(Mutex::new(Instant::now())));
+        let client = client(&clock);
+
+        // Tests: admin:wrong
+        for _ in 0..2 {
+            assert_eq!(
+                status(&client, "/admin", "Basic YWRtaW46d3Jvbmc="),
+                Status::Unauthorized
+            );
+        }
+        // Tests: admin:hunter2
+        assert_eq!(
+            status(&client, "/admin", "Basic YWRtaW46aHVudGVyMg=="),
+            Status::Ok
+        );
+        // Tests: admin:wrong
+        for _ in 0..2 {
+            assert_eq!(
+                status(&client, "/admin", "Basic YWRtaW46d3Jvbmc="),
+                Status::Unauthorized
+            );
+        }
+        // Tests: admin:hunter2
+        assert_eq!(
+            status(&client, "/admin", "Basic YWRtaW46aHVudGVyMg=="),
+            Status::Ok
+        );
+
+        // Tests: admin:wrong
+        for _ in 0..3 {
+            status(&client, "/admin", "Basic YWRtaW46d3Jvbmc=");
+        }
+        // Tests: admin:hunter2
+        assert_eq!(
+            status(&client, "/admin", "Basic YWRtaW46aHVudGVyMg=="),
+            Status::TooManyRequests
+        );
+    }
+}
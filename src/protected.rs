@@ -0,0 +1,123 @@
+//! Contains the [Protected] responder returned by routes wrapped in the
+//! [basic_auth](crate::basic_auth) attribute, available with the `macros` feature
+
+use crate::{BasicAuthChallenge, BasicAuthError, CredentialVerifier, VerifiedBasicAuth};
+use rocket::http::Status;
+use rocket::request::Request;
+use rocket::response::{self, Responder};
+
+/// Request guard injected into protected routes, which never fails so that the
+/// route can respond with a challenge itself
+pub type ProtectedGuard<V> = Option<Result<VerifiedBasicAuth<V>, BasicAuthError>>;
+
+/// Response of a protected route, which is either the route's own response or
+/// the reason its credentials were rejected
+pub enum Protected<R> {
+    /// Credentials were accepted and the route ran
+    Allowed(R),
+
+    /// Credentials were missing or rejected by the verifier
+    Rejected(BasicAuthChallenge),
+
+    /// Credentials couldn't be checked, such as when they're badly formatted
+    Failed(Status),
+}
+
+impl<R> Protected<R> {
+    /// Checks the outcome of the injected guard, challenging for the given
+    /// `realm` or the route's realm if credentials weren't accepted
+    pub fn check<V: CredentialVerifier>(
+        auth: ProtectedGuard<V>,
+        realm: Option<&str>,
+    ) -> Result<(), Self> {
+        match auth {
+            Some(Ok(_)) => Ok(()),
+            Some(Err(err)) if Status::from(&err) != Status::Unauthorized => {
+                Err(Protected::Failed(Status::from(&err)))
+            }
+            _ => Err(Protected::Rejected(
+                realm.map(BasicAuthChallenge::new).unwrap_or_default(),
+            )),
+        }
+    }
+}
+
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Protected<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        match self {
+            Protected::Allowed(response) => response.respond_to(request),
+            Protected::Rejected(challenge) => challenge.respond_to(request),
+            Protected::Failed(status) => Err(status),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{basic_auth, CredentialVerifier};
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+
+    struct Verifier;
+
+    #[rocket::async_trait]
+    impl CredentialVerifier for Verifier {
+        async fn verify(&self, username: &str, password: &str) -> bool {
+            username == "name" && password == "password"
+        }
+    }
+
+    #[basic_auth(realm = "Admin", verifier = Verifier)]
+    #[get("/admin")]
+    fn admin() -> &'static str {
+        "admin"
+    }
+
+    #[basic_auth(verifier = Verifier)]
+    #[get("/hello/<name>")]
+    async fn hello(name: &str) -> String {
+        format!("Hello, {}!", name)
+    }
+
+    #[test]
+    fn protected_routes() {
+        let rocket = rocket::build()
+            .manage(Verifier)
+            .mount("/", routes![admin, hello]);
+        let client = Client::tracked(rocket).unwrap();
+        let get = |path: &'static str, header: Option<&'static str>| {
+            let mut req = client.get(path);
+            if let Some(header) = header {
+                req = req.header(Header::new("Authorization", header));
+            }
+            let resp = req.dispatch();
+            (
+                resp.status(),
+                resp.headers()
+                    .get_one("WWW-Authenticate")
+                    .map(str::to_string),
+                resp.into_string(),
+            )
+        };
+
+        // Tests: name:password
+        let (status, _, body) = get("/admin", Some("Basic bmFtZTpwYXNzd29yZA=="));
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body.as_deref(), Some("admin"));
+        let (status, _, body) = get("/hello/world", Some("Basic bmFtZTpwYXNzd29yZA=="));
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body.as_deref(), Some("Hello, world!"));
+
+        let (status, challenge, _) = get("/admin", None);
+        assert_eq!(status, Status::Unauthorized);
+        assert_eq!(challenge.as_deref(), Some("Basic realm=\"Admin\""));
+        // Tests: name:wrong
+        let (status, challenge, _) = get("/hello/world", Some("Basic bmFtZTp3cm9uZw=="));
+        assert_eq!(status, Status::Unauthorized);
+        assert_eq!(challenge.as_deref(), Some("Basic realm=\"Restricted\""));
+        let (status, challenge, _) = get("/admin", Some("Basic !!!"));
+        assert_eq!(status, Status::BadRequest);
+        assert_eq!(challenge, None);
+    }
+}
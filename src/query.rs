@@ -0,0 +1,207 @@
+//! Contains the [QueryFallbackBasicAuth] request guard which falls back to
+//! reading credentials from query parameters
+
+use crate::{invalid_credentials, BasicAuth, BasicAuthConfig, BasicAuthError};
+#[cfg(feature = "log")]
+use log::trace;
+use rocket::outcome::Outcome;
+use rocket::request::{self, FromRequest, Request};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+/// The names of the query parameters which [QueryFallbackBasicAuth] reads
+/// credentials from when no `Authorization` header was sent
+///
+/// Like [CredentialHeader](crate::CredentialHeader), this stands in for const
+/// generic `&'static str` parameters
+pub trait CredentialParams {
+    /// Name of the parameter holding the username
+    const USERNAME: &'static str;
+
+    /// Name of the parameter holding the password
+    const PASSWORD: &'static str;
+}
+
+/// The `access_user` and `access_pass` query parameters, used by default for
+/// [QueryFallbackBasicAuth]
+#[derive(Debug)]
+pub struct AccessParams;
+
+impl CredentialParams for AccessParams {
+    const USERNAME: &'static str = "access_user";
+    const PASSWORD: &'static str = "access_pass";
+}
+
+/// A request guard like [BasicAuth] which falls back to reading credentials
+/// from the [CredentialParams] `P` query parameters, by default
+/// `?access_user=...&access_pass=...`, when no `Authorization` header was sent
+///
+/// This is only for constrained clients which can't set headers, such as
+/// `<img>` tags, and is **much less secure** than the header: query strings
+/// end up inside of browser history, `Referer` headers, proxy logs and
+/// Rocket's own request logs, leaking the password in plaintext. Only use it
+/// on the routes which need it, ideally with credentials that are limited to
+/// them, as nothing else reads credentials from the query
+///
+/// Credentials from the header always take priority, and badly formatted ones
+/// fail like they do for [BasicAuth] rather than falling back. Requests with
+/// only one of the two parameters fail with `400 Bad Request` and
+/// [BasicAuthError::Invalid], whilst requests with neither are handled the
+/// same as [BasicAuth] handles missing credentials
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::QueryFallbackBasicAuth;
+///
+/// #[get("/avatar.png")]
+/// fn avatar(auth: QueryFallbackBasicAuth) -> String {
+///     format!("Avatar of {}", auth.username)
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().mount("/", routes![avatar])
+/// }
+/// ```
+pub struct QueryFallbackBasicAuth<P: CredentialParams = AccessParams> {
+    inner: BasicAuth,
+    params: PhantomData<fn() -> P>,
+}
+
+impl<P: CredentialParams> QueryFallbackBasicAuth<P> {
+    /// Unwraps the [BasicAuth] credentials
+    pub fn into_inner(self) -> BasicAuth {
+        self.inner
+    }
+
+    fn new(inner: BasicAuth) -> Self {
+        Self {
+            inner,
+            params: PhantomData,
+        }
+    }
+}
+
+impl<P: CredentialParams> Deref for QueryFallbackBasicAuth<P> {
+    type Target = BasicAuth;
+
+    fn deref(&self) -> &BasicAuth {
+        &self.inner
+    }
+}
+
+impl<P: CredentialParams> fmt::Debug for QueryFallbackBasicAuth<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("QueryFallbackBasicAuth")
+            .field(&self.inner)
+            .finish()
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, P: CredentialParams> FromRequest<'r> for QueryFallbackBasicAuth<P> {
+    type Error = BasicAuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let outcome = BasicAuth::from_request(request).await;
+        if !matches!(
+            outcome,
+            Outcome::Forward(_) | Outcome::Error((_, BasicAuthError::Missing))
+        ) {
+            return outcome.map(Self::new);
+        }
+
+        let username = request.query_value::<String>(P::USERNAME);
+        let password = request.query_value::<String>(P::PASSWORD);
+        match (username, password) {
+            (None, None) => outcome.map(Self::new),
+            (Some(Ok(username)), Some(Ok(password))) => {
+                if BasicAuthConfig::from_request(request).require_non_empty_username
+                    && username.is_empty()
+                {
+                    return invalid_credentials(request);
+                }
+
+                #[cfg(feature = "log")]
+                trace!("Read basic authentication credentials from query parameters");
+                Outcome::Success(Self::new(BasicAuth { username, password }))
+            }
+            _ => invalid_credentials(request),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+
+    struct ShortParams;
+
+    impl CredentialParams for ShortParams {
+        const USERNAME: &'static str = "u";
+        const PASSWORD: &'static str = "p";
+    }
+
+    #[get("/default")]
+    fn default(auth: QueryFallbackBasicAuth) -> String {
+        format!("{}:{}", auth.username, auth.password)
+    }
+
+    #[get("/short")]
+    fn short(auth: QueryFallbackBasicAuth<ShortParams>) -> String {
+        format!("{}:{}", auth.username, auth.password)
+    }
+
+    #[test]
+    fn query_fallback_guard() {
+        let rocket = rocket::build().mount("/", routes![default, short]);
+        let client = Client::tracked(rocket).unwrap();
+        let get = |uri: &'static str, header: Option<&'static str>| {
+            let mut req = client.get(uri);
+            if let Some(header) = header {
+                req = req.header(Header::new("Authorization", header));
+            }
+            let resp = req.dispatch();
+            (resp.status(), resp.into_string())
+        };
+
+        // Tests: name:password, preferred over the query
+        assert_eq!(
+            get(
+                "/default?access_user=other&access_pass=secret",
+                Some("Basic bmFtZTpwYXNzd29yZA==")
+            ),
+            (Status::Ok, Some("name:password".to_string()))
+        );
+        assert_eq!(
+            get("/default?access_user=name&access_pass=pass%20word", None),
+            (Status::Ok, Some("name:pass word".to_string()))
+        );
+        assert_eq!(
+            get("/short?u=name&p=password", None),
+            (Status::Ok, Some("name:password".to_string()))
+        );
+        assert_eq!(
+            get("/short?access_user=name&access_pass=password", None).0,
+            Status::Unauthorized
+        );
+        assert_eq!(get("/default", None).0, Status::Unauthorized);
+        assert_eq!(get("/default?access_user=name", None).0, Status::BadRequest);
+        // badly formatted headers don't fall back
+        assert_eq!(
+            get(
+                "/default?access_user=name&access_pass=password",
+                Some("Basic !!!")
+            )
+            .0,
+            Status::BadRequest
+        );
+    }
+}
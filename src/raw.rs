@@ -0,0 +1,159 @@
+//! Contains the [RawBasicAuth] request guard which keeps passwords as raw bytes
+
+use crate::{
+    authorization_header, invalid_credentials, split_credentials, strip_scheme, BasicAuthConfig,
+    BasicAuthError,
+};
+use rocket::outcome::{try_outcome, Outcome};
+use rocket::request::{self, FromRequest, Request};
+use std::fmt;
+
+/// A request guard like [BasicAuth](crate::BasicAuth) which keeps the password
+/// as its raw decoded bytes, so passwords which aren't valid UTF-8 such as
+/// Latin-1 ones still come through
+///
+/// [RFC 7617](https://datatracker.ietf.org/doc/html/rfc7617#section-2) allows
+/// passwords to contain any octets, whilst the username still has to be valid
+/// UTF-8 here
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::RawBasicAuth;
+///
+/// #[get("/")]
+/// fn index(auth: RawBasicAuth) -> String {
+///     format!("Hello, {}! Your password is {} bytes long", auth.username, auth.password.len())
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().mount("/", routes![index])
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct RawBasicAuth {
+    /// Required username
+    pub username: String,
+
+    /// Required password as raw bytes
+    pub password: Vec<u8>,
+}
+
+impl RawBasicAuth {
+    /// Creates a new [RawBasicAuth] struct/request guard from a given plaintext
+    /// http auth header or returns a [Option::None] if invalid
+    pub fn new<T: Into<String>>(auth_header: T) -> Option<Self> {
+        Self::with_config(auth_header, &BasicAuthConfig::default())
+    }
+
+    /// Creates a new [RawBasicAuth] struct/request guard like [RawBasicAuth::new],
+    /// decoding according to the given `config` instead of the defaults
+    pub fn with_config<T: Into<String>>(auth_header: T, config: &BasicAuthConfig) -> Option<Self> {
+        let key = auth_header.into();
+        let token = strip_scheme(&key, "Basic")?;
+        if !config.allows_token(token) {
+            return None;
+        }
+        let decoded = config.decode_base64(token)?;
+        let (username, password) = split_credentials(&decoded, config).ok()?;
+
+        let username = String::from_utf8(decoded[username].to_vec()).ok()?;
+        let password = decoded[password].to_vec();
+
+        Some(Self { username, password })
+    }
+}
+
+impl fmt::Debug for RawBasicAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawBasicAuth")
+            .field("username", &self.username)
+            .field("password", &"[redacted]")
+            .finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for RawBasicAuth {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.password);
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RawBasicAuth {
+    type Error = BasicAuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let key = try_outcome!(authorization_header(request));
+        match RawBasicAuth::with_config(key, BasicAuthConfig::from_request(request)) {
+            Some(auth) => Outcome::Success(auth),
+            None => invalid_credentials(request),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::Header;
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+
+    #[get("/")]
+    fn index(auth: RawBasicAuth) -> String {
+        format!("{}:{:?}", auth.username, auth.password)
+    }
+
+    #[test]
+    fn new_check() {
+        // Tests: name:caf 0xE9
+        let auth = RawBasicAuth::new("Basic bmFtZTpjYWbp").unwrap();
+        assert_eq!(auth.username, "name");
+        assert_eq!(auth.password, b"caf\xe9");
+        // Tests: name:pass:word
+        assert_eq!(
+            RawBasicAuth::new("Basic bmFtZTpwYXNzOndvcmQ=")
+                .unwrap()
+                .password,
+            b"pass:word"
+        );
+        // Tests: 0xE9:password
+        assert!(RawBasicAuth::new("Basic 6TpwYXNzd29yZA==").is_none());
+        // Tests: nocolon
+        assert!(RawBasicAuth::new("Basic bm9jb2xvbg==").is_none());
+        assert!(RawBasicAuth::new("Basic !!!").is_none());
+    }
+
+    #[test]
+    fn with_config_check() {
+        let config = BasicAuthConfig {
+            separator: '|',
+            trim_credentials: true,
+            require_non_empty_username: true,
+            ..BasicAuthConfig::default()
+        };
+        // Tests: " name |caf 0xE9 "
+        let auth = RawBasicAuth::with_config("Basic IG5hbWUgfGNhZukg", &config).unwrap();
+        assert_eq!(auth.username, "name");
+        assert_eq!(auth.password, b"caf\xe9");
+        // Tests: name:password
+        assert!(RawBasicAuth::with_config("Basic bmFtZTpwYXNzd29yZA==", &config).is_none());
+        // Tests: |password
+        assert!(RawBasicAuth::with_config("Basic fHBhc3N3b3Jk", &config).is_none());
+    }
+
+    #[test]
+    fn raw_guard() {
+        let client = Client::tracked(rocket::build().mount("/", routes![index])).unwrap();
+        // Tests: name:caf 0xE9
+        let resp = client
+            .get("/")
+            .header(Header::new("Authorization", "Basic bmFtZTpjYWbp"))
+            .dispatch();
+        assert_eq!(resp.into_string().unwrap(), "name:[99, 97, 102, 233]");
+    }
+}
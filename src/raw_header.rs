@@ -0,0 +1,160 @@
+//! Contains the [BasicAuthRaw] request guard which also keeps the original
+//! `Authorization` header, e.g. for forwarding it upstream
+
+use crate::{authorization_header, invalid_credentials, BasicAuth, BasicAuthError};
+use rocket::http::Header;
+use rocket::outcome::{try_outcome, Outcome};
+use rocket::request::{self, FromRequest, Request};
+use std::fmt;
+
+/// A request guard like [BasicAuth] which also keeps the full original value
+/// of the `Authorization` header, for reverse proxy routes which need to send
+/// it on upstream exactly as it arrived
+///
+/// The header is stored without any modification, so it's the same even when
+/// decoding was lenient, e.g. with
+/// [BasicAuthConfig::trim_credentials](crate::BasicAuthConfig::trim_credentials).
+/// Like the password, it's redacted from [Debug] output and wiped from memory
+/// with the `zeroize` feature, as it holds the password in base64
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::BasicAuthRaw;
+///
+/// #[get("/")]
+/// fn index(auth: BasicAuthRaw) -> String {
+///     // e.g. send `auth.header()` along with a request to an upstream server
+///     format!("Forwarding for {} with {} bytes of credentials", auth.username, auth.raw_header.len())
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().mount("/", routes![index])
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct BasicAuthRaw {
+    /// Required username
+    pub username: String,
+
+    /// Required password
+    pub password: String,
+
+    /// Full original value of the `Authorization` header, including its scheme
+    pub raw_header: String,
+}
+
+impl BasicAuthRaw {
+    /// Recreates the original `Authorization` header for sending upstream
+    pub fn header(&self) -> Header<'static> {
+        Header::new("Authorization", self.raw_header.clone())
+    }
+}
+
+impl fmt::Debug for BasicAuthRaw {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BasicAuthRaw")
+            .field("username", &self.username)
+            .field("password", &"[redacted]")
+            .field("raw_header", &"[redacted]")
+            .finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for BasicAuthRaw {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.password);
+        zeroize::Zeroize::zeroize(&mut self.raw_header);
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for BasicAuthRaw {
+    type Error = BasicAuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let mut auth = try_outcome!(BasicAuth::from_request(request).await);
+        let raw_header = match authorization_header(request) {
+            Outcome::Success(key) => key.to_string(),
+            // already checked by `BasicAuth`, so this can't happen
+            _ => return invalid_credentials(request),
+        };
+
+        // taken rather than moved as fields can't be moved out with `zeroize`
+        Outcome::Success(Self {
+            username: std::mem::take(&mut auth.username),
+            password: std::mem::take(&mut auth.password),
+            raw_header,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BasicAuthConfig;
+    use rocket::http::Status;
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+
+    #[get("/")]
+    fn index(auth: BasicAuthRaw) -> String {
+        format!("{}:{}|{}", auth.username, auth.password, auth.raw_header)
+    }
+
+    #[test]
+    fn raw_header_guard() {
+        let config = BasicAuthConfig {
+            trim_credentials: true,
+            ..BasicAuthConfig::default()
+        };
+        let rocket = rocket::build().manage(config).mount("/", routes![index]);
+        let client = Client::tracked(rocket).unwrap();
+        let get = |header: &'static str| {
+            let resp = client
+                .get("/")
+                .header(Header::new("Authorization", header))
+                .dispatch();
+            (resp.status(), resp.into_string())
+        };
+
+        // Tests: name:password
+        assert_eq!(
+            get("Basic bmFtZTpwYXNzd29yZA=="),
+            (
+                Status::Ok,
+                Some("name:password|Basic bmFtZTpwYXNzd29yZA==".to_string())
+            )
+        );
+        // Tests: name:password with trailing space, trimmed but kept raw
+        assert_eq!(
+            get("bAsIc\tbmFtZTpwYXNzd29yZCA="),
+            (
+                Status::Ok,
+                Some("name:password|bAsIc\tbmFtZTpwYXNzd29yZCA=".to_string())
+            )
+        );
+        assert_eq!(get("Basic !!!").0, Status::BadRequest);
+        assert_eq!(client.get("/").dispatch().status(), Status::Unauthorized);
+    }
+
+    #[test]
+    fn raw_header_roundtrip() {
+        let auth = BasicAuthRaw {
+            username: "name".to_string(),
+            password: "password".to_string(),
+            raw_header: "Basic bmFtZTpwYXNzd29yZA==".to_string(),
+        };
+        let header = auth.header();
+        assert_eq!(header.name(), "Authorization");
+        assert_eq!(header.value(), "Basic bmFtZTpwYXNzd29yZA==");
+        assert_eq!(
+            format!("{:?}", auth),
+            "BasicAuthRaw { username: \"name\", password: \"[redacted]\", raw_header: \"[redacted]\" }"
+        );
+    }
+}
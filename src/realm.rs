@@ -0,0 +1,138 @@
+//! Contains the [Realm] request guard which sets the realm challenges are made
+//! with for a single route
+
+use rocket::outcome::Outcome;
+use rocket::request::{self, FromRequest, Request};
+use std::convert::Infallible;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Realm shown inside of the browser's login prompt for routes using the
+/// [Realm] request guard, a stand-in for a `&str` const generic
+pub trait RealmName: Send + Sync + 'static {
+    /// Realm sent inside of `WWW-Authenticate` headers
+    const REALM: &'static str;
+}
+
+/// Realm set for the current request by a [Realm] request guard
+pub(crate) struct RouteRealm(pub(crate) Option<&'static str>);
+
+impl RouteRealm {
+    /// Gets the realm set for the `request`, if any
+    pub(crate) fn get(request: &Request<'_>) -> Option<&'static str> {
+        request.local_cache(|| RouteRealm(None)).0
+    }
+}
+
+/// A request guard which never fails, making [BasicAuthChallenge](crate::BasicAuthChallenge)s
+/// without an explicit realm use the realm of `R` for this route instead of
+/// the one from [BasicAuthConfig](crate::BasicAuthConfig)
+///
+/// Request guards run in the order they're written, so this has to come
+/// before the guard which fails authentication
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::{basic_auth_catcher, BasicAuth, Realm, RealmName};
+///
+/// struct AdminArea;
+///
+/// impl RealmName for AdminArea {
+///     const REALM: &'static str = "Admin Area";
+/// }
+///
+/// #[get("/admin")]
+/// fn admin(_realm: Realm<AdminArea>, auth: BasicAuth) -> String {
+///     format!("Welcome to the admin area, {}!", auth.username)
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build()
+///         .mount("/", routes![admin])
+///         .register("/", catchers![basic_auth_catcher])
+/// }
+/// ```
+pub struct Realm<R: RealmName> {
+    realm: PhantomData<fn() -> R>,
+}
+
+impl<R: RealmName> fmt::Debug for Realm<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Realm").field(&R::REALM).finish()
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, R: RealmName> FromRequest<'r> for Realm<R> {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        request.local_cache(|| RouteRealm(Some(R::REALM)));
+        Outcome::Success(Self { realm: PhantomData })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{basic_auth_catcher, BasicAuth};
+    use rocket::http::Status;
+    use rocket::local::blocking::Client;
+    use rocket::{catchers, get, routes};
+
+    struct AdminArea;
+
+    impl RealmName for AdminArea {
+        const REALM: &'static str = "Admin Area";
+    }
+
+    struct Api;
+
+    impl RealmName for Api {
+        const REALM: &'static str = "API";
+    }
+
+    #[get("/admin")]
+    fn admin(_realm: Realm<AdminArea>, auth: BasicAuth) -> String {
+        auth.username.clone()
+    }
+
+    #[get("/api")]
+    fn api(_realm: Realm<Api>, auth: BasicAuth) -> String {
+        auth.username.clone()
+    }
+
+    #[get("/")]
+    fn index(auth: BasicAuth) -> String {
+        auth.username.clone()
+    }
+
+    #[test]
+    fn route_realms() {
+        let rocket = rocket::build()
+            .mount("/", routes![admin, api, index])
+            .register("/", catchers![basic_auth_catcher]);
+        let client = Client::tracked(rocket).unwrap();
+        let challenge = |path: &'static str| {
+            let resp = client.get(path).dispatch();
+            assert_eq!(resp.status(), Status::Unauthorized);
+            resp.headers()
+                .get_one("WWW-Authenticate")
+                .map(str::to_string)
+        };
+
+        assert_eq!(
+            challenge("/admin").as_deref(),
+            Some("Basic realm=\"Admin Area\"")
+        );
+        assert_eq!(challenge("/api").as_deref(), Some("Basic realm=\"API\""));
+        assert_eq!(
+            challenge("/").as_deref(),
+            Some("Basic realm=\"Restricted\"")
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! Contains the [RoleProvider] trait, its [Roles] managed state and the
+//! [BasicAuthWithRoles] request guard which yields verified credentials
+//! alongside their user's roles
+
+use crate::verifier::verify_request;
+use crate::{BasicAuth, BasicAuthError, CredentialVerifier};
+use rocket::http::Status;
+use rocket::outcome::{try_outcome, Outcome};
+use rocket::request::{self, FromRequest, Request};
+use std::fmt;
+use std::ops::Deref;
+
+/// A [CredentialVerifier] which also provides the roles `R` of the users it
+/// verifies, such as a list of role names or a bitflag of permissions, used by
+/// the [BasicAuthWithRoles] request guard
+///
+/// Providers are retrieved from Rocket's managed state wrapped inside of a
+/// [Roles], so make sure to [manage](rocket::Rocket::manage) one when building
+/// your app. As this trait is asynchronous, implementations need the
+/// [rocket::async_trait] attribute
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::{BasicAuthWithRoles, CredentialVerifier, RoleProvider, Roles};
+/// use std::collections::HashMap;
+///
+/// /// Simple provider holding plaintext passwords and roles in memory
+/// struct InMemoryProvider {
+///     users: HashMap<String, (String, Vec<String>)>,
+/// }
+///
+/// #[rocket::async_trait]
+/// impl CredentialVerifier for InMemoryProvider {
+///     async fn verify(&self, username: &str, password: &str) -> bool {
+///         self.users.get(username).map(|(p, _)| p == password).unwrap_or(false)
+///     }
+/// }
+///
+/// #[rocket::async_trait]
+/// impl RoleProvider<Vec<String>> for InMemoryProvider {
+///     async fn roles(&self, username: &str) -> Vec<String> {
+///         self.users.get(username).map(|(_, roles)| roles.clone()).unwrap_or_default()
+///     }
+/// }
+///
+/// #[get("/")]
+/// fn index(auth: BasicAuthWithRoles<Vec<String>>) -> String {
+///     format!("Welcome back, {}! Roles: {}", auth.username, auth.roles.join(", "))
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let mut users = HashMap::new();
+///     users.insert("alice".to_string(), ("wonderland".to_string(), vec!["admin".to_string()]));
+///
+///     rocket::build()
+///         .manage(Roles::new(InMemoryProvider { users }))
+///         .mount("/", routes![index])
+/// }
+/// ```
+#[rocket::async_trait]
+pub trait RoleProvider<R>: CredentialVerifier {
+    /// Gets the roles of the user `username`, whose credentials have already
+    /// been accepted by [CredentialVerifier::verify]
+    async fn roles(&self, username: &str) -> R;
+}
+
+/// Managed state holding the [RoleProvider] which [BasicAuthWithRoles] guards
+/// verify credentials and get roles of type `R` with, see [RoleProvider] for an
+/// example
+pub struct Roles<R>(Box<dyn RoleProvider<R>>);
+
+impl<R> Roles<R> {
+    /// Creates new managed state verifying credentials and getting roles with
+    /// the given `provider`
+    pub fn new<P: RoleProvider<R>>(provider: P) -> Self {
+        Self(Box::new(provider))
+    }
+}
+
+impl<R> fmt::Debug for Roles<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Roles").finish_non_exhaustive()
+    }
+}
+
+/// A request guard containing [BasicAuth] credentials which have been checked
+/// by the [RoleProvider] inside of Rocket's [Roles] managed state, alongside
+/// the roles `R` it provided for their user, which it dereferences into
+///
+/// This merges checking credentials and looking up what their user may do, so
+/// routes don't have to fetch the roles again. Rejected credentials and users
+/// the provider doesn't [permit](CredentialVerifier::permits) are handled like
+/// [VerifiedBasicAuth](crate::VerifiedBasicAuth) handles them, whilst [Roles]
+/// missing from managed state fails with `500 Internal Server Error` and
+/// [BasicAuthError::Unmanaged]
+///
+/// See [RoleProvider] for a full example
+pub struct BasicAuthWithRoles<R> {
+    inner: BasicAuth,
+
+    /// Roles of the authenticated user
+    pub roles: R,
+}
+
+impl<R> BasicAuthWithRoles<R> {
+    /// Unwraps the verified [BasicAuth] credentials and their user's roles
+    pub fn into_parts(self) -> (BasicAuth, R) {
+        (self.inner, self.roles)
+    }
+}
+
+impl<R> Deref for BasicAuthWithRoles<R> {
+    type Target = BasicAuth;
+
+    fn deref(&self) -> &BasicAuth {
+        &self.inner
+    }
+}
+
+impl<R: fmt::Debug> fmt::Debug for BasicAuthWithRoles<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BasicAuthWithRoles")
+            .field("inner", &self.inner)
+            .field("roles", &self.roles)
+            .finish()
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, R: Send + 'static> FromRequest<'r> for BasicAuthWithRoles<R> {
+    type Error = BasicAuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let provider = match request.rocket().state::<Roles<R>>() {
+            Some(provider) => &*provider.0,
+            None => {
+                return Outcome::Error((Status::InternalServerError, BasicAuthError::Unmanaged))
+            }
+        };
+        let auth = try_outcome!(verify_request(request, provider).await);
+        let roles = provider.roles(&auth.username).await;
+
+        Outcome::Success(Self { inner: auth, roles })
+    }
+}
@@ -0,0 +1,226 @@
+//! Contains the [SqlxVerifier] and its [SqlxBasicAuth] request guard, which
+//! check credentials against password hashes stored inside of a database,
+//! available with the `sqlx` feature
+
+use crate::store::DUMMY_PASSWORD;
+use crate::{hash, BasicAuth, BasicAuthError, FailureTracker};
+#[cfg(feature = "log")]
+use log::trace;
+use rocket::http::Status;
+use rocket::outcome::{try_outcome, Outcome};
+use rocket::request::{self, FromRequest, Request};
+use rocket::tokio::task;
+use sqlx::{ColumnIndex, Database, Decode, Encode, Executor, IntoArguments, Pool, Row, Type};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+/// Bcrypt hash of the dummy password using the default cost, which unknown
+/// usernames are verified against until the hash of a known one has been seen
+const DUMMY_HASH: &str = "$2b$12$BINXPwdeQagUB3aMBZa7fOV3vlXC4M4taz0iKeCQ4urHI7oithRE.";
+
+/// Verifies credentials against argon2 or bcrypt password hashes stored inside
+/// of a database, used by the [SqlxBasicAuth] request guard and available with
+/// the `sqlx` feature
+///
+/// The hash is fetched using a parameterized query given the username as its
+/// only parameter, e.g. `SELECT password_hash FROM users WHERE name = ?`, which
+/// should return at most one row. The hash is read from its `password_hash`
+/// column by default, which can be changed using [SqlxVerifier::hash_column].
+/// Like [BasicAuth::verify_hash], argon2 and bcrypt hashes are detected from
+/// their prefix and hashes which can't be parsed never match
+///
+/// Hashes are verified on Rocket's blocking thread pool so they don't stall
+/// other requests. Unknown usernames are verified against a dummy hash so that
+/// they take as long as known ones, which uses the same algorithm and cost as
+/// the first known user's hash once one has been fetched, and a bcrypt hash
+/// with the default cost before then
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::{SqlxBasicAuth, SqlxVerifier};
+/// use sqlx::{Sqlite, SqlitePool};
+///
+/// #[get("/")]
+/// fn index(auth: SqlxBasicAuth<Sqlite>) -> String {
+///     format!("Welcome back, {}!", auth.username)
+/// }
+///
+/// #[launch]
+/// async fn rocket() -> _ {
+///     let pool = SqlitePool::connect("sqlite://users.db").await.unwrap();
+///     let verifier = SqlxVerifier::new(pool, "SELECT hash FROM users WHERE name = ?")
+///         .hash_column("hash");
+///
+///     rocket::build()
+///         .manage(verifier)
+///         .mount("/", routes![index])
+/// }
+/// ```
+pub struct SqlxVerifier<DB: Database> {
+    pool: Pool<DB>,
+    query: String,
+    hash_column: String,
+    dummy: OnceLock<String>,
+}
+
+impl<DB: Database> SqlxVerifier<DB> {
+    /// Creates a new verifier fetching password hashes from the `pool` using
+    /// the parameterized `query`, which is given the username
+    pub fn new<T: Into<String>>(pool: Pool<DB>, query: T) -> Self {
+        Self {
+            pool,
+            query: query.into(),
+            hash_column: "password_hash".to_string(),
+            dummy: OnceLock::new(),
+        }
+    }
+
+    /// Reads password hashes from the given `column` of the query's results
+    /// instead of `password_hash`
+    pub fn hash_column<T: Into<String>>(mut self, column: T) -> Self {
+        self.hash_column = column.into();
+        self
+    }
+}
+
+impl<DB> SqlxVerifier<DB>
+where
+    DB: Database,
+    for<'c> &'c Pool<DB>: Executor<'c, Database = DB>,
+    for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'r> String: Decode<'r, DB> + Type<DB>,
+    for<'i> &'i str: ColumnIndex<DB::Row>,
+{
+    /// Checks if the given `username` and `password` are valid credentials,
+    /// being [Result::Ok] with `false` if the user doesn't exist or their
+    /// password doesn't match, and failing if the database couldn't be queried
+    /// or the hash column couldn't be read
+    pub async fn verify(&self, username: &str, password: &str) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query(&self.query)
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let (hash, known) = match row {
+            Some(row) => (row.try_get(self.hash_column.as_str())?, true),
+            None => (
+                self.dummy
+                    .get()
+                    .map_or(DUMMY_HASH, String::as_str)
+                    .to_string(),
+                false,
+            ),
+        };
+        let learn = known && self.dummy.get().is_none();
+        let password = password.to_string();
+        let (matched, dummy) = task::spawn_blocking(move || {
+            let matched = hash::verify_password_hash(&password, &hash);
+            (
+                matched,
+                learn.then(|| hash::hash_like(DUMMY_PASSWORD, &hash)),
+            )
+        })
+        .await
+        .unwrap_or((false, None));
+
+        if let Some(dummy) = dummy {
+            let _ = self.dummy.set(dummy);
+        }
+        Ok(matched & known)
+    }
+}
+
+impl<DB: Database> fmt::Debug for SqlxVerifier<DB> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqlxVerifier")
+            .field("query", &self.query)
+            .field("hash_column", &self.hash_column)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A request guard containing [BasicAuth] credentials which have been checked
+/// by the [SqlxVerifier] for the database `DB` inside of Rocket's managed
+/// state, which it dereferences into, available with the `sqlx` feature
+///
+/// Requests with credentials the verifier rejects are forwarded with a
+/// `401 Unauthorized` status just like requests missing them entirely, whilst
+/// database errors fail with `500 Internal Server Error` and
+/// [BasicAuthError::Unavailable] rather than rejecting the credentials, and a
+/// verifier missing from managed state fails the same way with
+/// [BasicAuthError::Unmanaged]
+///
+/// See [SqlxVerifier] for a full example
+pub struct SqlxBasicAuth<DB: Database> {
+    inner: BasicAuth,
+    database: PhantomData<fn() -> DB>,
+}
+
+impl<DB: Database> SqlxBasicAuth<DB> {
+    /// Unwraps the verified [BasicAuth] credentials
+    pub fn into_inner(self) -> BasicAuth {
+        self.inner
+    }
+}
+
+impl<DB: Database> Deref for SqlxBasicAuth<DB> {
+    type Target = BasicAuth;
+
+    fn deref(&self) -> &BasicAuth {
+        &self.inner
+    }
+}
+
+impl<DB: Database> fmt::Debug for SqlxBasicAuth<DB> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SqlxBasicAuth").field(&self.inner).finish()
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, DB> FromRequest<'r> for SqlxBasicAuth<DB>
+where
+    DB: Database,
+    for<'c> &'c Pool<DB>: Executor<'c, Database = DB>,
+    for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'s> String: Decode<'s, DB> + Type<DB>,
+    for<'i> &'i str: ColumnIndex<DB::Row>,
+{
+    type Error = BasicAuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let verifier = match request.rocket().state::<SqlxVerifier<DB>>() {
+            Some(verifier) => verifier,
+            None => {
+                return Outcome::Error((Status::InternalServerError, BasicAuthError::Unmanaged))
+            }
+        };
+        let auth = try_outcome!(BasicAuth::from_request(request).await);
+
+        match verifier.verify(&auth.username, &auth.password).await {
+            Ok(true) => {
+                FailureTracker::request_succeeded(request);
+                Outcome::Success(Self {
+                    inner: auth,
+                    database: PhantomData,
+                })
+            }
+            Ok(false) => {
+                FailureTracker::request_failed(request);
+                Outcome::Forward(Status::Unauthorized)
+            }
+            Err(_err) => {
+                #[cfg(feature = "log")]
+                trace!("Basic authentication database query failed, {}", _err);
+                Outcome::Error((Status::InternalServerError, BasicAuthError::Unavailable))
+            }
+        }
+    }
+}
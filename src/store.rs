@@ -0,0 +1,506 @@
+//! Contains the [BasicAuthStore] in-memory user store and its [StoreBasicAuth]
+//! request guard
+
+use crate::{CredentialVerifier, VerifiedBasicAuth};
+use rocket::tokio::task;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::iter::FromIterator;
+#[cfg(feature = "htpasswd")]
+use std::{fs, io, path::Path};
+use subtle::{Choice, ConstantTimeEq};
+
+/// A small in-memory store of usernames and their passwords, which can be
+/// [managed](rocket::Rocket::manage) by Rocket to verify [StoreBasicAuth]
+/// request guards
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::{BasicAuthStore, StoreBasicAuth};
+///
+/// #[get("/")]
+/// fn index(auth: StoreBasicAuth) -> String {
+///     format!("Welcome back, {}!", auth.username)
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let mut store = BasicAuthStore::new();
+///     store.insert("alice", "wonderland");
+///     store.insert("bob", "builder");
+///
+///     rocket::build().manage(store).mount("/", routes![index])
+/// }
+/// ```
+#[derive(Clone)]
+pub struct BasicAuthStore {
+    users: HashMap<String, Password>,
+    case_insensitive: bool,
+
+    /// Password which unknown usernames are checked against
+    dummy: Password,
+}
+
+/// Password of a user inside of a [BasicAuthStore]
+#[derive(Clone)]
+enum Password {
+    /// Plaintext password inserted with [BasicAuthStore::insert]
+    Plain(String),
+
+    /// Hash loaded from a `.htpasswd` file
+    #[cfg(feature = "htpasswd")]
+    Htpasswd(String),
+
+    /// Argon2 or bcrypt hashes inserted with [BasicAuthStore::insert_hashes]
+    #[cfg(feature = "hash")]
+    Hashes(Vec<String>),
+}
+
+impl BasicAuthStore {
+    /// Creates a new, empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new, empty store whose usernames are case-insensitive, like
+    /// email addresses often are, by lowercasing them on insertion and lookup
+    ///
+    /// Passwords stay case-sensitive and are still compared in constant-time
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_basicauth::BasicAuthStore;
+    ///
+    /// let mut store = BasicAuthStore::new_case_insensitive();
+    /// store.insert("Alice", "wonderland");
+    /// assert!(store.contains("alice", "wonderland"));
+    /// assert!(store.contains("ALICE", "wonderland"));
+    /// assert!(!store.contains("alice", "WONDERLAND"));
+    /// ```
+    pub fn new_case_insensitive() -> Self {
+        Self {
+            case_insensitive: true,
+            ..Self::default()
+        }
+    }
+
+    /// Loads a store from the Apache `.htpasswd` file at `path`, available
+    /// with the `htpasswd` feature
+    ///
+    /// Entries hashed with bcrypt (`$2y$`), apr1 (`$apr1$`) and SHA (`{SHA}`)
+    /// are supported, whilst entries in any other format are skipped with a
+    /// warning instead of failing the whole load
+    #[cfg(feature = "htpasswd")]
+    pub fn from_htpasswd<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut store = Self::new();
+        for (username, hash) in crate::htpasswd::parse(&fs::read_to_string(path)?) {
+            store.insert_password(username, Password::Htpasswd(hash));
+        }
+        Ok(store)
+    }
+
+    /// Inserts a user into the store, replacing their password if they
+    /// already exist
+    pub fn insert<U: Into<String>, P: Into<String>>(&mut self, username: U, password: P) {
+        self.insert_password(username.into(), Password::Plain(password.into()));
+    }
+
+    /// Inserts a user into the store with any number of argon2
+    /// [PHC string](https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md)
+    /// or bcrypt password `hashes`, replacing their password if they already
+    /// exist, available with the `hash` feature
+    ///
+    /// The user's credentials are valid if the password matches any of the
+    /// hashes, e.g. both an old and a new one whilst rotating their password.
+    /// Every hash is always checked, even after a match, so the time taken
+    /// doesn't reveal which of them matched
+    ///
+    /// The first time hashes are inserted, the store also hashes a dummy
+    /// password the same way for unknown usernames to be verified against,
+    /// so this takes as long as verifying them
+    #[cfg(feature = "hash")]
+    pub fn insert_hashes<U: Into<String>>(&mut self, username: U, hashes: Vec<String>) {
+        self.insert_password(username.into(), Password::Hashes(hashes));
+    }
+
+    /// Gets the number of users in the store
+    pub fn len(&self) -> usize {
+        self.users.len()
+    }
+
+    /// Checks if the store has no users
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+
+    /// Removes a user from the store, returning if they existed
+    pub fn remove(&mut self, username: &str) -> bool {
+        let username = self.key(username);
+        self.users.remove(username.as_ref()).is_some()
+    }
+
+    /// Checks if the store contains a user with the given `username` and
+    /// `password`, comparing the password in constant-time
+    ///
+    /// Unknown usernames are still compared against a dummy password so that
+    /// they take as long as known usernames with a wrong password, stopping
+    /// attackers from finding out which usernames exist through timing. The
+    /// time taken does depend on the length of the given password, and the
+    /// username lookup itself isn't constant-time. Once the store holds hashes,
+    /// loaded from `.htpasswd` files or inserted with `insert_hashes`, unknown
+    /// usernames are instead verified against dummy hashes using the same
+    /// algorithms and costs as the first hashed user's password
+    pub fn contains(&self, username: &str, password: &str) -> bool {
+        let (expected, known) = self.expected(username);
+        // non-short-circuiting `&` so the password is always checked
+        expected.matches(password) & known
+    }
+
+    /// Gets the password a `username`'s credentials are checked against, being
+    /// the dummy password if they're unknown, and whether they're known
+    fn expected(&self, username: &str) -> (&Password, bool) {
+        match self.users.get(self.key(username).as_ref()) {
+            Some(expected) => (expected, true),
+            None => (&self.dummy, false),
+        }
+    }
+
+    /// Inserts a user's `password` under the key for their `username`
+    fn insert_password(&mut self, username: String, password: Password) {
+        let username = match self.key(&username) {
+            Cow::Owned(key) => key,
+            Cow::Borrowed(_) => username,
+        };
+        // only the first hashed password's dummy is kept, so later ones aren't
+        // hashed again just to be thrown away
+        if matches!(self.dummy, Password::Plain(_)) {
+            if let Some(dummy) = password.dummy() {
+                self.dummy = dummy;
+            }
+        }
+        self.users.insert(username, password);
+    }
+
+    /// Gets the key a `username` is stored under, lowercasing it if the store
+    /// is case-insensitive
+    fn key<'a>(&self, username: &'a str) -> Cow<'a, str> {
+        if self.case_insensitive {
+            Cow::Owned(username.to_lowercase())
+        } else {
+            Cow::Borrowed(username)
+        }
+    }
+}
+
+impl Default for BasicAuthStore {
+    fn default() -> Self {
+        Self {
+            users: HashMap::new(),
+            case_insensitive: false,
+            dummy: Password::Plain(DUMMY_PASSWORD.to_string()),
+        }
+    }
+}
+
+impl fmt::Debug for BasicAuthStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BasicAuthStore")
+            .field("users", &self.users)
+            .field("case_insensitive", &self.case_insensitive)
+            .finish()
+    }
+}
+
+impl Password {
+    /// Creates a dummy password for unknown usernames, hashed the same way as
+    /// this one so that verifying it takes as long, or [Option::None] if this
+    /// password isn't hashed
+    fn dummy(&self) -> Option<Password> {
+        match self {
+            Password::Plain(_) => None,
+            #[cfg(feature = "htpasswd")]
+            Password::Htpasswd(hash) => Some(Password::Htpasswd(crate::htpasswd::hash_like(
+                DUMMY_PASSWORD,
+                hash,
+            ))),
+            #[cfg(feature = "hash")]
+            Password::Hashes(hashes) if hashes.is_empty() => None,
+            #[cfg(feature = "hash")]
+            Password::Hashes(hashes) => Some(Password::Hashes(
+                hashes
+                    .iter()
+                    .map(|hash| crate::hash::hash_like(DUMMY_PASSWORD, hash))
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Checks if this password is hashed rather than in plaintext
+    fn is_hashed(&self) -> bool {
+        !matches!(self, Password::Plain(_))
+    }
+
+    /// Checks if the given `password` matches this one
+    fn matches(&self, password: &str) -> bool {
+        match self {
+            Password::Plain(expected) => compare(expected, password),
+            #[cfg(feature = "htpasswd")]
+            Password::Htpasswd(hash) => crate::htpasswd::verify(password, hash),
+            #[cfg(feature = "hash")]
+            Password::Hashes(hashes) => hashes.iter().fold(false, |matched, hash| {
+                crate::hash::verify_password_hash(password, hash) | matched
+            }),
+        }
+    }
+}
+
+impl fmt::Debug for Password {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\"[redacted]\"")
+    }
+}
+
+/// Builds a store from pairs of `(username, password)`, e.g. collected from a
+/// configuration map, where later pairs replace the passwords of earlier ones
+/// with the same username
+///
+/// # Example
+///
+/// ```rust
+/// use rocket_basicauth::BasicAuthStore;
+///
+/// let store: BasicAuthStore = vec![("alice", "wonderland"), ("bob", "builder")]
+///     .into_iter()
+///     .collect();
+/// assert_eq!(store.len(), 2);
+/// assert!(store.contains("bob", "builder"));
+/// ```
+impl<U: Into<String>, P: Into<String>> FromIterator<(U, P)> for BasicAuthStore {
+    fn from_iter<I: IntoIterator<Item = (U, P)>>(iter: I) -> Self {
+        let mut store = Self::new();
+        store.extend(iter);
+        store
+    }
+}
+
+/// Inserts pairs of `(username, password)` like [BasicAuthStore::insert]
+impl<U: Into<String>, P: Into<String>> Extend<(U, P)> for BasicAuthStore {
+    fn extend<I: IntoIterator<Item = (U, P)>>(&mut self, iter: I) {
+        for (username, password) in iter {
+            self.insert(username, password);
+        }
+    }
+}
+
+/// Password which unknown usernames are compared against
+pub(crate) const DUMMY_PASSWORD: &str = "rocket-basicauth dummy password";
+
+/// Compares a `given` password against the `expected` one in constant-time,
+/// taking time depending only on the length of the given password
+fn compare(expected: &str, given: &str) -> bool {
+    let expected = expected.as_bytes();
+    let mut equal: Choice = expected.len().ct_eq(&given.len());
+    for (ind, byte) in given.bytes().enumerate() {
+        equal &= expected.get(ind).copied().unwrap_or(0).ct_eq(&byte);
+    }
+    equal.into()
+}
+
+#[rocket::async_trait]
+impl CredentialVerifier for BasicAuthStore {
+    async fn verify(&self, username: &str, password: &str) -> bool {
+        let (expected, known) = self.expected(username);
+        let matched = if expected.is_hashed() {
+            // hashing takes a while, so it's kept off of Rocket's async workers
+            let (expected, password) = (expected.clone(), password.to_string());
+            task::spawn_blocking(move || expected.matches(&password))
+                .await
+                .unwrap_or(false)
+        } else {
+            expected.matches(password)
+        };
+        matched & known
+    }
+}
+
+/// A request guard containing credentials which have been checked against the
+/// [BasicAuthStore] inside of Rocket's managed state, see [VerifiedBasicAuth]
+/// for its behaviour
+pub type StoreBasicAuth = VerifiedBasicAuth<BasicAuthStore>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+
+    #[get("/")]
+    fn index(auth: StoreBasicAuth) -> String {
+        auth.username.clone()
+    }
+
+    #[test]
+    fn store_check() {
+        let mut store = BasicAuthStore::new();
+        store.insert("alice", "wonderland");
+        store.insert("bob", "builder");
+        assert!(store.contains("alice", "wonderland"));
+        assert!(!store.contains("alice", "builder"));
+        assert!(!store.contains("carol", "wonderland"));
+        assert!(store.remove("bob"));
+        assert!(!store.remove("bob"));
+        assert!(!store.contains("bob", "builder"));
+        assert_eq!(
+            format!("{:?}", store),
+            "BasicAuthStore { users: {\"alice\": \"[redacted]\"}, case_insensitive: false }"
+        );
+    }
+
+    #[test]
+    fn store_collect() {
+        let pairs = vec![
+            ("alice".to_string(), "wonderland".to_string()),
+            ("bob".to_string(), "builder".to_string()),
+            ("alice".to_string(), "looking-glass".to_string()),
+        ];
+        let mut store: BasicAuthStore = pairs.into_iter().collect();
+        assert_eq!(store.len(), 2);
+        assert!(!store.is_empty());
+        assert!(store.contains("alice", "looking-glass"));
+        assert!(!store.contains("alice", "wonderland"));
+        assert!(store.contains("bob", "builder"));
+
+        store.extend(vec![("carol", "singer")]);
+        assert_eq!(store.len(), 3);
+        assert!(store.contains("carol", "singer"));
+
+        let empty: BasicAuthStore = Vec::<(&str, &str)>::new().into_iter().collect();
+        assert_eq!(empty.len(), 0);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn store_case_insensitive() {
+        let mut store = BasicAuthStore::new_case_insensitive();
+        store.insert("Alice", "Wonderland");
+        assert!(store.contains("Alice", "Wonderland"));
+        assert!(store.contains("alice", "Wonderland"));
+        assert!(store.contains("ALICE", "Wonderland"));
+        assert!(!store.contains("alice", "wonderland"));
+        assert!(store.remove("aLiCe"));
+        assert!(!store.contains("alice", "Wonderland"));
+
+        let mut store = BasicAuthStore::new();
+        store.insert("Alice", "Wonderland");
+        assert!(store.contains("Alice", "Wonderland"));
+        assert!(!store.contains("alice", "Wonderland"));
+    }
+
+    #[test]
+    fn store_compares_unknown() {
+        let mut store = BasicAuthStore::new();
+        store.insert("alice", "wonderland");
+        assert!(matches!(
+            store.expected("alice"),
+            (Password::Plain(expected), true) if expected == "wonderland"
+        ));
+        assert!(matches!(
+            store.expected("mallory"),
+            (Password::Plain(expected), false) if expected == DUMMY_PASSWORD
+        ));
+        // matching the dummy password still doesn't make unknown users valid
+        assert!(!store.contains("mallory", DUMMY_PASSWORD));
+
+        assert!(compare("wonderland", "wonderland"));
+        assert!(!compare("wonderland", "wonderlan"));
+        assert!(!compare("wonderland", "wonderlandd"));
+        assert!(!compare("", "x"));
+        assert!(compare("", ""));
+    }
+
+    #[test]
+    fn store_guard() {
+        let mut store = BasicAuthStore::new();
+        store.insert("alice", "wonderland");
+        let rocket = rocket::build().manage(store).mount("/", routes![index]);
+        let client = Client::tracked(rocket).unwrap();
+        let status = |header: &'static str| {
+            client
+                .get("/")
+                .header(Header::new("Authorization", header))
+                .dispatch()
+                .status()
+        };
+
+        // Tests: alice:wonderland
+        assert_eq!(status("Basic YWxpY2U6d29uZGVybGFuZA=="), Status::Ok);
+        // Tests: alice:wrong
+        assert_eq!(status("Basic YWxpY2U6d3Jvbmc="), Status::Unauthorized);
+        // Tests: bob:builder
+        assert_eq!(status("Basic Ym9iOmJ1aWxkZXI="), Status::Unauthorized);
+    }
+
+    #[cfg(feature = "hash")]
+    #[test]
+    fn store_hashes() {
+        // Tests: hunter2, as the old hash
+        let old = "$2b$04$ak7hY0TyWkDxYULfbVPmGOfL3xGvfln9820rKJSc3ayrbNMuINe6C";
+        let new = bcrypt::hash("wonderland", 4).unwrap();
+        let mut store = BasicAuthStore::new();
+        store.insert_hashes("alice", vec![old.to_string(), new]);
+        assert!(store.contains("alice", "hunter2"));
+        assert!(store.contains("alice", "wonderland"));
+        assert!(!store.contains("alice", "builder"));
+        assert!(!store.contains("bob", "hunter2"));
+
+        // later hashed users keep the dummy hashes of the first one
+        store.insert_hashes("carol", vec![bcrypt::hash("singer", 5).unwrap()]);
+        assert!(store.contains("carol", "singer"));
+
+        // unknown users are verified against dummy hashes of the same cost
+        match store.expected("bob") {
+            (Password::Hashes(dummies), false) => {
+                assert_eq!(dummies.len(), 2);
+                for dummy in dummies {
+                    assert!(dummy.starts_with("$2b$04$"));
+                    assert!(crate::hash::verify_password_hash(DUMMY_PASSWORD, dummy));
+                }
+            }
+            _ => panic!("unknown users should be verified against dummy hashes"),
+        }
+        assert!(!store.contains("bob", DUMMY_PASSWORD));
+
+        store.insert_hashes("alice", Vec::new());
+        assert!(!store.contains("alice", "hunter2"));
+    }
+
+    #[cfg(feature = "htpasswd")]
+    #[test]
+    fn store_htpasswd() {
+        let store = BasicAuthStore::from_htpasswd(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/users.htpasswd"
+        ))
+        .unwrap();
+        assert!(store.contains("alice", "hunter2"));
+        assert!(!store.contains("alice", "myPassword"));
+        assert!(store.contains("bob", "myPassword"));
+        assert!(!store.contains("bob", "hunter2"));
+        assert!(!store.contains("carol", "plaintext"));
+        // unknown users are verified against a dummy hash like alice's
+        match store.expected("carol") {
+            (Password::Htpasswd(dummy), false) => {
+                assert!(dummy.starts_with("$2b$04$"));
+                assert!(crate::htpasswd::verify(DUMMY_PASSWORD, dummy));
+            }
+            _ => panic!("unknown users should be verified against a dummy hash"),
+        }
+        assert!(BasicAuthStore::from_htpasswd("does/not/exist").is_err());
+    }
+}
@@ -0,0 +1,95 @@
+//! Helpers for testing routes protected by basic authentication using Rocket's
+//! local clients, available with the `test-util` feature
+//!
+//! # Example
+//!
+//! ```rust
+//! #[macro_use] extern crate rocket;
+//!
+//! use rocket::local::blocking::Client;
+//! use rocket_basicauth::test_util::blocking::with_basic_auth;
+//! use rocket_basicauth::BasicAuth;
+//!
+//! #[get("/")]
+//! fn index(auth: BasicAuth) -> String {
+//!     format!("Hello, {}!", auth.username)
+//! }
+//!
+//! let client = Client::tracked(rocket::build().mount("/", routes![index])).unwrap();
+//! let resp = with_basic_auth(client.get("/"), "name", "password").dispatch();
+//! assert_eq!(resp.into_string().unwrap(), "Hello, name!");
+//! ```
+
+use crate::BasicAuth;
+use rocket::http::Header;
+
+/// Creates the `Authorization` header for the given credentials
+fn authorization(username: &str, password: &str) -> Header<'static> {
+    Header::new("Authorization", BasicAuth::encode(username, password))
+}
+
+/// Helpers for [rocket::local::blocking] clients
+pub mod blocking {
+    use rocket::local::blocking::LocalRequest;
+
+    /// Adds an `Authorization` header containing the given `username` and
+    /// `password` to a local `request`
+    pub fn with_basic_auth<'c>(
+        request: LocalRequest<'c>,
+        username: &str,
+        password: &str,
+    ) -> LocalRequest<'c> {
+        request.header(super::authorization(username, password))
+    }
+}
+
+/// Helpers for [rocket::local::asynchronous] clients
+pub mod asynchronous {
+    use rocket::local::asynchronous::LocalRequest;
+
+    /// Adds an `Authorization` header containing the given `username` and
+    /// `password` to a local `request`
+    pub fn with_basic_auth<'c>(
+        request: LocalRequest<'c>,
+        username: &str,
+        password: &str,
+    ) -> LocalRequest<'c> {
+        request.header(super::authorization(username, password))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::Status;
+    use rocket::{get, routes};
+
+    #[get("/")]
+    fn index(auth: BasicAuth) -> String {
+        format!("{}:{}", auth.username, auth.password)
+    }
+
+    #[test]
+    fn blocking_helper() {
+        let client =
+            rocket::local::blocking::Client::tracked(rocket::build().mount("/", routes![index]))
+                .unwrap();
+        let resp = blocking::with_basic_auth(client.get("/"), "ünï", "pass:word").dispatch();
+        assert_eq!(resp.status(), Status::Ok);
+        assert_eq!(resp.into_string().unwrap(), "ünï:pass:word");
+    }
+
+    #[rocket::async_test]
+    async fn asynchronous_helper() {
+        let client = rocket::local::asynchronous::Client::tracked(
+            rocket::build().mount("/", routes![index]),
+        )
+        .await
+        .unwrap();
+        let resp = asynchronous::with_basic_auth(client.get("/"), "name", "password")
+            .dispatch()
+            .await;
+        assert_eq!(resp.status(), Status::Ok);
+        assert_eq!(resp.into_string().await.unwrap(), "name:password");
+    }
+}
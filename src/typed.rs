@@ -0,0 +1,113 @@
+//! Contains the [TypedBasicAuth] request guard which parses usernames into
+//! another type
+
+use crate::{invalid_credentials, BasicAuth, BasicAuthError};
+use rocket::outcome::{try_outcome, Outcome};
+use rocket::request::{self, FromRequest, Request};
+use std::fmt;
+use std::str::FromStr;
+
+/// A request guard like [BasicAuth] whose username is parsed into `U` using
+/// [FromStr], such as a numeric user id, failing with `400 Bad Request` and
+/// [BasicAuthError::Invalid] if parsing fails
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::TypedBasicAuth;
+///
+/// #[get("/")]
+/// fn index(auth: TypedBasicAuth<u64>) -> String {
+///     format!("Hello, user #{}!", auth.username)
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().mount("/", routes![index])
+/// }
+/// ```
+pub struct TypedBasicAuth<U: FromStr> {
+    /// Required username, parsed from its string form
+    pub username: U,
+
+    /// Required password
+    pub password: String,
+}
+
+impl<U: FromStr + fmt::Debug> fmt::Debug for TypedBasicAuth<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedBasicAuth")
+            .field("username", &self.username)
+            .field("password", &"[redacted]")
+            .finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<U: FromStr> Drop for TypedBasicAuth<U> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.password);
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, U: FromStr + Send> FromRequest<'r> for TypedBasicAuth<U> {
+    type Error = BasicAuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let (username, password) = try_outcome!(BasicAuth::from_request(request).await).into();
+
+        match username.parse() {
+            Ok(username) => Outcome::Success(Self { username, password }),
+            Err(_) => invalid_credentials(request),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+
+    #[get("/")]
+    fn index(auth: TypedBasicAuth<u64>) -> String {
+        (auth.username + 1).to_string()
+    }
+
+    #[test]
+    fn typed_guard() {
+        let client = Client::tracked(rocket::build().mount("/", routes![index])).unwrap();
+        let get = |header: &'static str| {
+            let resp = client
+                .get("/")
+                .header(Header::new("Authorization", header))
+                .dispatch();
+            (resp.status(), resp.into_string())
+        };
+
+        // Tests: 41:password
+        assert_eq!(
+            get("Basic NDE6cGFzc3dvcmQ="),
+            (Status::Ok, Some("42".to_string()))
+        );
+        // Tests: abc:password
+        assert_eq!(get("Basic YWJjOnBhc3N3b3Jk").0, Status::BadRequest);
+        assert_eq!(client.get("/").dispatch().status(), Status::Unauthorized);
+    }
+
+    #[test]
+    fn typed_debug_redacted() {
+        let auth = TypedBasicAuth {
+            username: 41u64,
+            password: "password".to_string(),
+        };
+        assert_eq!(
+            format!("{:?}", auth),
+            "TypedBasicAuth { username: 41, password: \"[redacted]\" }"
+        );
+    }
+}
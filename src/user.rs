@@ -0,0 +1,40 @@
+//! Contains the [VerifyBasicAuth] trait for checking credentials against your
+//! own user types
+
+use crate::BasicAuth;
+
+/// A user type which [BasicAuth] credentials can be checked against, such as a
+/// row loaded from your database
+///
+/// This can be derived for structs using [BasicAuthVerify](crate::BasicAuthVerify)
+/// with the `macros` and `hash` features, which checks the username in
+/// constant-time and the password against an argon2 or bcrypt hash like
+/// [BasicAuth::verify_hash] does
+///
+/// # Example
+///
+/// ```rust
+/// use rocket_basicauth::{BasicAuth, VerifyBasicAuth};
+///
+/// struct User {
+///     name: String,
+///     password: String,
+/// }
+///
+/// impl VerifyBasicAuth for User {
+///     fn verify(&self, auth: &BasicAuth) -> bool {
+///         auth.verify(&self.name, &self.password)
+///     }
+/// }
+///
+/// let user = User {
+///     name: "name".to_string(),
+///     password: "password".to_string(),
+/// };
+/// // Tests: name:password
+/// assert!(user.verify(&BasicAuth::new("Basic bmFtZTpwYXNzd29yZA==").unwrap()));
+/// ```
+pub trait VerifyBasicAuth {
+    /// Checks if the given `auth` credentials belong to this user
+    fn verify(&self, auth: &BasicAuth) -> bool;
+}
@@ -0,0 +1,79 @@
+//! Contains the [BasicUsername] request guard for routes which only need the
+//! claimed username
+
+use crate::{BasicAuth, BasicAuthError};
+use rocket::outcome::{try_outcome, Outcome};
+use rocket::request::{self, FromRequest, Request};
+
+/// A request guard containing only the username claimed by [BasicAuth]
+/// credentials, for routes which e.g. tag logs with it whilst verification
+/// happens elsewhere
+///
+/// The password is discarded as soon as the credentials are decoded, being
+/// wiped from memory with the `zeroize` feature, and malformed credentials
+/// fail just like they do for [BasicAuth]. Note that the username is **not**
+/// verified in any way
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rocket;
+///
+/// use rocket_basicauth::BasicUsername;
+///
+/// #[get("/")]
+/// fn index(username: BasicUsername) -> String {
+///     format!("You claim to be {}", username.0)
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().mount("/", routes![index])
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicUsername(pub String);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for BasicUsername {
+    type Error = BasicAuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let mut auth = try_outcome!(BasicAuth::from_request(request).await);
+        // taken so the rest of the credentials are dropped straight away
+        Outcome::Success(Self(std::mem::take(&mut auth.username)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+
+    #[get("/")]
+    fn index(username: BasicUsername) -> String {
+        format!("{:?}", username)
+    }
+
+    #[test]
+    fn username_guard() {
+        let client = Client::tracked(rocket::build().mount("/", routes![index])).unwrap();
+        let get = |header: &'static str| {
+            let resp = client
+                .get("/")
+                .header(Header::new("Authorization", header))
+                .dispatch();
+            (resp.status(), resp.into_string())
+        };
+
+        // Tests: name:password, where the password isn't kept
+        assert_eq!(
+            get("Basic bmFtZTpwYXNzd29yZA=="),
+            (Status::Ok, Some("BasicUsername(\"name\")".to_string()))
+        );
+        assert_eq!(get("Basic !!!").0, Status::BadRequest);
+        assert_eq!(client.get("/").dispatch().status(), Status::Unauthorized);
+    }
+}
@@ -0,0 +1,81 @@
+//! Tests loading users straight from the [AuthenticatedUser] guard
+
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+use rocket::{get, routes};
+use rocket_basicauth::{AuthenticatedUser, UserLoader, Users};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+struct User {
+    id: u64,
+    name: String,
+}
+
+/// Loader holding users and their plaintext passwords in memory
+struct InMemoryLoader {
+    users: HashMap<String, (String, User)>,
+}
+
+#[rocket::async_trait]
+impl UserLoader<User> for InMemoryLoader {
+    async fn load(&self, username: &str, password: &str) -> Option<User> {
+        let (expected, user) = self.users.get(username)?;
+        (expected == password).then(|| user.clone())
+    }
+}
+
+#[get("/")]
+fn index(user: AuthenticatedUser<User>) -> String {
+    format!("#{} {}", user.id, user.name)
+}
+
+fn get(client: &Client, header: &'static str) -> (Status, Option<String>) {
+    let resp = client
+        .get("/")
+        .header(Header::new("Authorization", header))
+        .dispatch();
+    (resp.status(), resp.into_string())
+}
+
+#[test]
+fn authenticated_user() {
+    let mut users = HashMap::new();
+    let alice = User {
+        id: 1,
+        name: "Alice".to_string(),
+    };
+    users.insert("alice".to_string(), ("wonderland".to_string(), alice));
+    let rocket = rocket::build()
+        .manage(Users::new(InMemoryLoader { users }))
+        .mount("/", routes![index]);
+    let client = Client::tracked(rocket).unwrap();
+
+    // Tests: alice:wonderland
+    assert_eq!(
+        get(&client, "Basic YWxpY2U6d29uZGVybGFuZA=="),
+        (Status::Ok, Some("#1 Alice".to_string()))
+    );
+    // Tests: alice:wrong
+    assert_eq!(
+        get(&client, "Basic YWxpY2U6d3Jvbmc=").0,
+        Status::Unauthorized
+    );
+    // Tests: bob:builder
+    assert_eq!(
+        get(&client, "Basic Ym9iOmJ1aWxkZXI=").0,
+        Status::Unauthorized
+    );
+    assert_eq!(client.get("/").dispatch().status(), Status::Unauthorized);
+}
+
+#[test]
+fn authenticated_user_unmanaged() {
+    let client = Client::tracked(rocket::build().mount("/", routes![index])).unwrap();
+
+    // Tests: alice:wonderland
+    assert_eq!(
+        get(&client, "Basic YWxpY2U6d29uZGVybGFuZA==").0,
+        Status::InternalServerError
+    );
+}
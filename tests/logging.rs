@@ -0,0 +1,83 @@
+//! Tests the reasons logged for failed authentication, which is in its own
+//! test binary as only one logger can ever be set per process
+#![cfg(feature = "log")]
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+use rocket::{get, routes};
+use rocket_basicauth::BasicAuth;
+use std::sync::Mutex;
+
+/// Logger keeping the messages logged by this crate
+struct TestLogger(Mutex<Vec<String>>);
+
+impl Log for TestLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target().starts_with("rocket_basicauth")
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) && record.level() == Level::Trace {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: TestLogger = TestLogger(Mutex::new(Vec::new()));
+
+#[get("/")]
+fn index(auth: BasicAuth) -> String {
+    auth.username.clone()
+}
+
+#[test]
+fn failure_reasons() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let client = Client::tracked(rocket::build().mount("/", routes![index])).unwrap();
+    let failure = |headers: &[&'static str]| {
+        LOGGER.0.lock().unwrap().clear();
+        let mut req = client.get("/");
+        for header in headers {
+            req = req.header(Header::new("Authorization", *header));
+        }
+        assert_eq!(req.dispatch().status(), Status::BadRequest);
+        let logged = LOGGER.0.lock().unwrap().clone();
+        logged
+            .into_iter()
+            .find(|message| message.starts_with("Basic authentication failed"))
+            .unwrap()
+    };
+
+    assert_eq!(
+        failure(&["Basic !!!"]),
+        "Basic authentication failed, bad base64: credentials are not validly base64-encoded"
+    );
+    // Tests: nocolon
+    assert_eq!(
+        failure(&["Basic bm9jb2xvbg=="]),
+        "Basic authentication failed, no separator: decoded credentials have no colon between username and password"
+    );
+    // Tests: 0xFF 0xFE
+    assert_eq!(
+        failure(&["Basic //4="]),
+        "Basic authentication failed, bad utf-8: decoded credentials are not valid UTF-8"
+    );
+    // Tests: name:password, twice
+    assert_eq!(
+        failure(&["Basic bmFtZTpwYXNzd29yZA==", "Basic bmFtZTpwYXNzd29yZA=="]),
+        "Basic authentication failed, bad count: expected a single authorization header"
+    );
+    // Tests: name:hunter2
+    failure(&["Basic bmFtZTpodW50ZXIy!"]);
+    assert!(LOGGER
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|message| !message.contains("hunter2")));
+}
@@ -0,0 +1,66 @@
+//! Tests protecting part of an app using [BasicAuthRocketExt::protect]
+
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+use rocket::{get, routes};
+use rocket_basicauth::{BasicAuthRocketExt, BasicAuthStore};
+
+#[get("/")]
+fn index() -> &'static str {
+    "index"
+}
+
+#[get("/")]
+fn admin() -> &'static str {
+    "admin"
+}
+
+#[get("/users")]
+fn users() -> &'static str {
+    "users"
+}
+
+#[test]
+fn protect_admin() {
+    let mut store = BasicAuthStore::new();
+    store.insert("alice", "wonderland");
+    let rocket = rocket::build()
+        .mount("/", routes![index])
+        .mount("/admin", routes![admin, users])
+        .protect("/admin", store);
+    let client = Client::tracked(rocket).unwrap();
+    let get = |path: &'static str, header: Option<&'static str>| {
+        let mut req = client.get(path);
+        if let Some(header) = header {
+            req = req.header(Header::new("Authorization", header));
+        }
+        let resp = req.dispatch();
+        (
+            resp.status(),
+            resp.headers()
+                .get_one("WWW-Authenticate")
+                .map(str::to_string),
+            resp.into_string(),
+        )
+    };
+
+    let (status, _, body) = get("/", None);
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body.as_deref(), Some("index"));
+
+    for path in ["/admin", "/admin/users"] {
+        let (status, challenge, _) = get(path, None);
+        assert_eq!(status, Status::Unauthorized);
+        assert_eq!(challenge.as_deref(), Some("Basic realm=\"Restricted\""));
+        // Tests: alice:wrong
+        assert_eq!(
+            get(path, Some("Basic YWxpY2U6d3Jvbmc=")).0,
+            Status::Unauthorized
+        );
+    }
+
+    // Tests: alice:wonderland
+    let (status, _, body) = get("/admin/users", Some("Basic YWxpY2U6d29uZGVybGFuZA=="));
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body.as_deref(), Some("users"));
+}
@@ -0,0 +1,42 @@
+//! Tests the `protected` example's login prompt from the outside
+
+#[allow(dead_code)]
+#[path = "../examples/protected.rs"]
+mod protected;
+
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+
+#[test]
+fn protected_example() {
+    let client = Client::tracked(protected::rocket()).unwrap();
+    let get = |header: Option<&'static str>| {
+        let mut req = client.get("/");
+        if let Some(header) = header {
+            req = req.header(Header::new("Authorization", header));
+        }
+        let resp = req.dispatch();
+        (
+            resp.status(),
+            resp.headers()
+                .get_one("WWW-Authenticate")
+                .map(str::to_string),
+            resp.into_string(),
+        )
+    };
+    let challenge = Some("Basic realm=\"Protected Example\", charset=\"UTF-8\"".to_string());
+
+    // Tests: admin:hunter2
+    let (status, header, body) = get(Some("Basic YWRtaW46aHVudGVyMg=="));
+    assert_eq!(status, Status::Ok);
+    assert_eq!(header, None);
+    assert_eq!(body.as_deref(), Some("Welcome back, admin!"));
+
+    let (status, header, _) = get(None);
+    assert_eq!(status, Status::Unauthorized);
+    assert_eq!(header, challenge);
+    // Tests: admin:wrong
+    let (status, header, _) = get(Some("Basic YWRtaW46d3Jvbmc="));
+    assert_eq!(status, Status::Unauthorized);
+    assert_eq!(header, challenge);
+}
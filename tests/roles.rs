@@ -0,0 +1,96 @@
+//! Tests getting the roles of authenticated users from the
+//! [BasicAuthWithRoles] guard
+
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+use rocket::{get, routes};
+use rocket_basicauth::{BasicAuthWithRoles, CredentialVerifier, RoleProvider, Roles};
+use std::collections::HashMap;
+
+/// Provider holding users, their plaintext passwords and roles in memory
+struct InMemoryProvider {
+    users: HashMap<String, (String, Vec<String>)>,
+}
+
+#[rocket::async_trait]
+impl CredentialVerifier for InMemoryProvider {
+    async fn verify(&self, username: &str, password: &str) -> bool {
+        self.users
+            .get(username)
+            .map(|(expected, _)| expected == password)
+            .unwrap_or(false)
+    }
+
+    async fn permits(&self, username: &str) -> bool {
+        username != "mallory"
+    }
+}
+
+#[rocket::async_trait]
+impl RoleProvider<Vec<String>> for InMemoryProvider {
+    async fn roles(&self, username: &str) -> Vec<String> {
+        self.users
+            .get(username)
+            .map(|(_, roles)| roles.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[get("/")]
+fn index(auth: BasicAuthWithRoles<Vec<String>>) -> String {
+    format!("{}: {}", auth.username, auth.roles.join(","))
+}
+
+fn get(client: &Client, header: &'static str) -> (Status, Option<String>) {
+    let resp = client
+        .get("/")
+        .header(Header::new("Authorization", header))
+        .dispatch();
+    (resp.status(), resp.into_string())
+}
+
+#[test]
+fn basic_auth_with_roles() {
+    let mut users = HashMap::new();
+    let roles = vec!["admin".to_string(), "editor".to_string()];
+    users.insert("alice".to_string(), ("wonderland".to_string(), roles));
+    users.insert("bob".to_string(), ("builder".to_string(), Vec::new()));
+    users.insert("mallory".to_string(), ("password".to_string(), Vec::new()));
+    let rocket = rocket::build()
+        .manage(Roles::new(InMemoryProvider { users }))
+        .mount("/", routes![index]);
+    let client = Client::tracked(rocket).unwrap();
+
+    // Tests: alice:wonderland
+    assert_eq!(
+        get(&client, "Basic YWxpY2U6d29uZGVybGFuZA=="),
+        (Status::Ok, Some("alice: admin,editor".to_string()))
+    );
+    // Tests: bob:builder
+    assert_eq!(
+        get(&client, "Basic Ym9iOmJ1aWxkZXI="),
+        (Status::Ok, Some("bob: ".to_string()))
+    );
+    // Tests: alice:wrong
+    assert_eq!(
+        get(&client, "Basic YWxpY2U6d3Jvbmc=").0,
+        Status::Unauthorized
+    );
+    // Tests: mallory:password
+    assert_eq!(
+        get(&client, "Basic bWFsbG9yeTpwYXNzd29yZA==").0,
+        Status::Forbidden
+    );
+    assert_eq!(client.get("/").dispatch().status(), Status::Unauthorized);
+}
+
+#[test]
+fn basic_auth_with_roles_unmanaged() {
+    let client = Client::tracked(rocket::build().mount("/", routes![index])).unwrap();
+
+    // Tests: alice:wonderland
+    assert_eq!(
+        get(&client, "Basic YWxpY2U6d29uZGVybGFuZA==").0,
+        Status::InternalServerError
+    );
+}
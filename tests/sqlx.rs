@@ -0,0 +1,131 @@
+//! Tests verifying credentials against an in-memory SQLite database with the
+//! [SqlxBasicAuth] guard
+#![cfg(feature = "sqlx")]
+
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::Client;
+use rocket::{get, routes};
+use rocket_basicauth::{SqlxBasicAuth, SqlxVerifier};
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::{Sqlite, SqlitePool};
+
+/// Bcrypt hash of `hunter2`
+const BCRYPT: &str = "$2b$04$ak7hY0TyWkDxYULfbVPmGOfL3xGvfln9820rKJSc3ayrbNMuINe6C";
+
+#[get("/")]
+fn index(auth: SqlxBasicAuth<Sqlite>) -> String {
+    auth.username.clone()
+}
+
+/// Creates a database holding the single user `alice` with the password
+/// `hunter2`, kept to one connection as each has its own in-memory database
+async fn pool() -> SqlitePool {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    sqlx::query("CREATE TABLE users (name TEXT PRIMARY KEY, hash TEXT NOT NULL)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO users (name, hash) VALUES (?, ?)")
+        .bind("alice")
+        .bind(BCRYPT)
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+async fn get(client: &Client, header: &'static str) -> (Status, Option<String>) {
+    let resp = client
+        .get("/")
+        .header(Header::new("Authorization", header))
+        .dispatch()
+        .await;
+    (resp.status(), resp.into_string().await)
+}
+
+#[rocket::async_test]
+async fn sqlx_verifier() {
+    let verifier = SqlxVerifier::new(pool().await, "SELECT hash FROM users WHERE name = ?")
+        .hash_column("hash");
+    assert_eq!(verifier.verify("alice", "hunter2").await.ok(), Some(true));
+    assert_eq!(verifier.verify("alice", "hunter3").await.ok(), Some(false));
+    assert_eq!(verifier.verify("bob", "hunter2").await.ok(), Some(false));
+    // matching the dummy hash unknown users are checked against doesn't help
+    assert_eq!(
+        verifier
+            .verify("bob", "rocket-basicauth dummy password")
+            .await
+            .ok(),
+        Some(false)
+    );
+
+    let rocket = rocket::build().manage(verifier).mount("/", routes![index]);
+    let client = Client::tracked(rocket).await.unwrap();
+
+    // Tests: alice:hunter2
+    assert_eq!(
+        get(&client, "Basic YWxpY2U6aHVudGVyMg==").await,
+        (Status::Ok, Some("alice".to_string()))
+    );
+    // Tests: alice:hunter3
+    assert_eq!(
+        get(&client, "Basic YWxpY2U6aHVudGVyMw==").await.0,
+        Status::Unauthorized
+    );
+    // Tests: bob:hunter2
+    assert_eq!(
+        get(&client, "Basic Ym9iOmh1bnRlcjI=").await.0,
+        Status::Unauthorized
+    );
+    assert_eq!(
+        client.get("/").dispatch().await.status(),
+        Status::Unauthorized
+    );
+}
+
+#[rocket::async_test]
+async fn sqlx_verifier_errors() {
+    // reads the default `password_hash` column, which doesn't exist
+    let verifier = SqlxVerifier::new(pool().await, "SELECT hash FROM users WHERE name = ?");
+    assert!(verifier.verify("alice", "hunter2").await.is_err());
+    // unknown users never reach the missing column
+    assert_eq!(verifier.verify("bob", "hunter2").await.ok(), Some(false));
+
+    let rocket = rocket::build().manage(verifier).mount("/", routes![index]);
+    let client = Client::tracked(rocket).await.unwrap();
+
+    // Tests: alice:hunter2
+    assert_eq!(
+        get(&client, "Basic YWxpY2U6aHVudGVyMg==").await.0,
+        Status::InternalServerError
+    );
+
+    let verifier = SqlxVerifier::new(pool().await, "SELECT hash FROM missing WHERE name = ?");
+    let rocket = rocket::build().manage(verifier).mount("/", routes![index]);
+    let client = Client::tracked(rocket).await.unwrap();
+
+    // Tests: alice:hunter2
+    assert_eq!(
+        get(&client, "Basic YWxpY2U6aHVudGVyMg==").await.0,
+        Status::InternalServerError
+    );
+}
+
+#[rocket::async_test]
+async fn sqlx_verifier_unmanaged() {
+    let client = Client::tracked(rocket::build().mount("/", routes![index]))
+        .await
+        .unwrap();
+
+    // Tests: alice:hunter2
+    assert_eq!(
+        get(&client, "Basic YWxpY2U6aHVudGVyMg==").await.0,
+        Status::InternalServerError
+    );
+}
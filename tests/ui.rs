@@ -0,0 +1,16 @@
+#![cfg(feature = "macros")]
+
+#[test]
+fn basic_auth_attribute() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/pass/*.rs");
+    cases.compile_fail("tests/ui/fail/*.rs");
+}
+
+#[cfg(feature = "hash")]
+#[test]
+fn basic_auth_verify_derive() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/derive/pass/*.rs");
+    cases.compile_fail("tests/ui/derive/fail/*.rs");
+}
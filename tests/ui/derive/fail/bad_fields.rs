@@ -0,0 +1,29 @@
+use rocket_basicauth::BasicAuthVerify;
+
+#[derive(BasicAuthVerify)]
+struct Unknown {
+    #[basic_auth(username)]
+    username: String,
+    #[basic_auth(password)]
+    password: String,
+}
+
+#[derive(BasicAuthVerify)]
+struct Duplicate {
+    #[basic_auth(username)]
+    username: String,
+    #[basic_auth(username)]
+    email: String,
+    #[basic_auth(password_hash)]
+    password_hash: String,
+}
+
+#[derive(BasicAuthVerify)]
+struct Tuple(String, String);
+
+#[derive(BasicAuthVerify)]
+enum User {
+    Admin,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use rocket_basicauth::BasicAuthVerify;
+
+#[derive(BasicAuthVerify)]
+struct User {
+    #[basic_auth(username)]
+    username: String,
+    password_hash: String,
+}
+
+fn main() {}
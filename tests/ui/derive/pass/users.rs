@@ -0,0 +1,39 @@
+use rocket_basicauth::{BasicAuth, BasicAuthVerify, VerifyBasicAuth};
+use std::borrow::Cow;
+
+#[derive(BasicAuthVerify)]
+struct User {
+    #[basic_auth(username)]
+    username: String,
+    #[basic_auth(password_hash)]
+    password_hash: String,
+}
+
+#[derive(BasicAuthVerify)]
+struct Row<'a, T> {
+    id: T,
+    #[basic_auth(password_hash)]
+    hash: &'a str,
+    #[basic_auth(username)]
+    name: Cow<'a, str>,
+}
+
+fn main() {
+    // Tests: name:hunter2
+    let auth = BasicAuth::new("Basic bmFtZTpodW50ZXIy").unwrap();
+    let hash = "$2b$04$ak7hY0TyWkDxYULfbVPmGOfL3xGvfln9820rKJSc3ayrbNMuINe6C";
+
+    let user = User {
+        username: "name".to_string(),
+        password_hash: hash.to_string(),
+    };
+    assert!(user.verify(&auth));
+
+    let row = Row {
+        id: 1,
+        hash,
+        name: Cow::Borrowed("other"),
+    };
+    assert!(!row.verify(&auth));
+    let _ = row.id;
+}
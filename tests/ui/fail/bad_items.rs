@@ -0,0 +1,21 @@
+use rocket_basicauth::{basic_auth, CredentialVerifier};
+
+struct Verifier;
+
+#[rocket::async_trait]
+impl CredentialVerifier for Verifier {
+    async fn verify(&self, _: &str, _: &str) -> bool {
+        false
+    }
+}
+
+#[basic_auth(verifier = Verifier)]
+struct Admin;
+
+#[basic_auth(verifier = Verifier)]
+#[rocket::get("/<a>/<b>")]
+fn pair((a, b): (String, String)) -> String {
+    a + &b
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use rocket_basicauth::basic_auth;
+
+#[basic_auth(realm = "Admin")]
+#[rocket::get("/admin")]
+fn admin() -> &'static str {
+    "admin"
+}
+
+fn main() {}
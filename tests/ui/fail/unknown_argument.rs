@@ -0,0 +1,24 @@
+use rocket_basicauth::{basic_auth, CredentialVerifier};
+
+struct Verifier;
+
+#[rocket::async_trait]
+impl CredentialVerifier for Verifier {
+    async fn verify(&self, _: &str, _: &str) -> bool {
+        false
+    }
+}
+
+#[basic_auth(realm = "Admin", verifier = Verifier, charset = "UTF-8")]
+#[rocket::get("/admin")]
+fn admin() -> &'static str {
+    "admin"
+}
+
+#[basic_auth(realm = "Admin", realm = "Other", verifier = Verifier)]
+#[rocket::get("/other")]
+fn other() -> &'static str {
+    "other"
+}
+
+fn main() {}
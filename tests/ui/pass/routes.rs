@@ -0,0 +1,36 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket_basicauth::{basic_auth, CredentialVerifier};
+
+struct Verifier;
+
+#[rocket::async_trait]
+impl CredentialVerifier for Verifier {
+    async fn verify(&self, username: &str, password: &str) -> bool {
+        username == "admin" && password == "hunter2"
+    }
+}
+
+#[basic_auth(realm = "Admin", verifier = Verifier)]
+#[get("/admin")]
+fn admin() -> &'static str {
+    "admin"
+}
+
+#[basic_auth(verifier = Verifier)]
+#[get("/hello/<name>")]
+async fn hello(mut name: String) -> String {
+    name.insert_str(0, "Hello, ");
+    name
+}
+
+#[basic_auth(verifier = Verifier, realm = "Status")]
+#[get("/status")]
+fn status() {}
+
+fn main() {
+    let _ = rocket::build()
+        .manage(Verifier)
+        .mount("/", routes![admin, hello, status]);
+}
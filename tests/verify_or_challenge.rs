@@ -0,0 +1,45 @@
+//! Tests checking credentials by hand inside of a handler with
+//! [verify_or_challenge]
+
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+use rocket::{get, routes};
+use rocket_basicauth::{verify_or_challenge, BasicAuth, BasicAuthChallenge};
+
+#[get("/")]
+fn index(auth: Option<BasicAuth>) -> Result<String, BasicAuthChallenge> {
+    let auth = verify_or_challenge(auth, "admin", "hunter2")?;
+    Ok(format!("Welcome back, {}!", auth.username))
+}
+
+#[test]
+fn verify_or_challenge_handler() {
+    let client = Client::tracked(rocket::build().mount("/", routes![index])).unwrap();
+    let get = |header: Option<&'static str>| {
+        let mut req = client.get("/");
+        if let Some(header) = header {
+            req = req.header(Header::new("Authorization", header));
+        }
+        let resp = req.dispatch();
+        (
+            resp.status(),
+            resp.headers()
+                .get_one("WWW-Authenticate")
+                .map(str::to_string),
+            resp.into_string(),
+        )
+    };
+
+    // Tests: admin:hunter2
+    assert_eq!(
+        get(Some("Basic YWRtaW46aHVudGVyMg==")),
+        (Status::Ok, None, Some("Welcome back, admin!".to_string()))
+    );
+    // Tests: admin:wrong
+    let (status, challenge, _) = get(Some("Basic YWRtaW46d3Jvbmc="));
+    assert_eq!(status, Status::Unauthorized);
+    assert_eq!(challenge.as_deref(), Some("Basic realm=\"Restricted\""));
+    let (status, challenge, _) = get(None);
+    assert_eq!(status, Status::Unauthorized);
+    assert_eq!(challenge.as_deref(), Some("Basic realm=\"Restricted\""));
+}